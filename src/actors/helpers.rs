@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashSet};
 use std::hash::Hash;
 
@@ -95,6 +96,16 @@ pub(crate) fn map_is_subset(
     true
 }
 
+/// Lowercases the string for case insensitive comparisons, borrows it otherwise.
+#[inline]
+pub(crate) fn fold_case(s: &str, case_insensitive: bool) -> Cow<'_, str> {
+    if case_insensitive {
+        Cow::Owned(s.to_lowercase())
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
 #[cfg(test)]
 mod tests_map_intersects {
     use super::*;
@@ -191,32 +202,32 @@ mod tests_array_intersects {
     use super::*;
     #[test]
     fn test_array_intersects() {
-        let left_arr = vec![DataNode::from(1), DataNode::from(2)];
-        let right_arr = vec![DataNode::from(2), DataNode::from(4)];
+        let left_arr = [DataNode::from(1), DataNode::from(2)];
+        let right_arr = [DataNode::from(2), DataNode::from(4)];
 
         assert!(str_array_intersects(left_arr.iter(), right_arr.iter()));
     }
 
     #[test]
     fn test_arrays_do_not_intersect() {
-        let left_arr = vec![DataNode::from(1), DataNode::from(2)];
-        let right_arr = vec![DataNode::from(4), DataNode::from(5)];
+        let left_arr = [DataNode::from(1), DataNode::from(2)];
+        let right_arr = [DataNode::from(4), DataNode::from(5)];
 
         assert!(!str_array_intersects(left_arr.iter(), right_arr.iter()));
     }
 
     #[test]
     fn test_array_intersects_left_is_empty() {
-        let left_arr = vec![];
-        let right_arr = vec![DataNode::from(2), DataNode::from(4)];
+        let left_arr = [];
+        let right_arr = [DataNode::from(2), DataNode::from(4)];
 
         assert!(str_array_intersects(left_arr.iter(), right_arr.iter()));
     }
 
     #[test]
     fn test_array_intersects_right_is_empty() {
-        let left_arr = vec![DataNode::from(1), DataNode::from(2), DataNode::from(3)];
-        let right_arr = vec![];
+        let left_arr = [DataNode::from(1), DataNode::from(2), DataNode::from(3)];
+        let right_arr = [];
 
         assert!(!str_array_intersects(left_arr.iter(), right_arr.iter()));
     }
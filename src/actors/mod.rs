@@ -1,2 +1,2 @@
-mod helpers;
+pub(crate) mod helpers;
 pub(crate) mod traits;
@@ -13,8 +13,14 @@ impl Contains for DataNode<'_> {
             DataNode::Array(arr) => arr.contains(other),
             DataNode::Map(m) => m.contains_key(other),
             DataNode::I64(_)
+            | DataNode::U64(_)
+            | DataNode::I128(_)
+            | DataNode::U128(_)
             | DataNode::F64(_)
+            | DataNode::Decimal(_)
             | DataNode::Str(_)
+            | DataNode::DateTime(_)
+            | DataNode::Duration(_)
             | DataNode::Bool(_)
             | DataNode::Null => unreachable!(),
         }
@@ -31,7 +37,9 @@ impl IsSubset for DataNode<'_> {
             (DataNode::Map(left_map), DataNode::Map(right_map)) => {
                 map_is_subset(left_map, right_map)
             }
-            (DataNode::Str(left_str), DataNode::Str(right_str)) => right_str.contains(left_str),
+            (DataNode::Str(left_str), DataNode::Str(right_str)) => {
+                right_str.contains(left_str.as_ref())
+            }
             _ => unreachable!(),
         }
     }
@@ -65,13 +73,6 @@ impl Intersects for DataNode<'_> {
 
 impl Compare for DataNode<'_> {
     fn compare(&self, operation: Op, other: &Self) -> bool {
-        match operation {
-            Op::Eq => self == other,
-            Op::Ne => self != other,
-            Op::Gt => self > other,
-            Op::Lt => self < other,
-            Op::Gte => self >= other,
-            Op::Lte => self <= other,
-        }
+        operation.is_satisfied_by(self.cmp(other))
     }
 }
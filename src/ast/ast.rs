@@ -1,14 +1,20 @@
-use crate::actors::traits::{Compare, Contains, Intersects, IsSubset, IsSuperset};
+use std::{borrow::Cow, collections::BTreeSet};
+
+use crate::actors::{
+    helpers::fold_case,
+    traits::{Compare, Contains, Intersects, IsSubset, IsSuperset},
+};
+use crate::ast::{Context, DataPath, Operand, Subject};
 use crate::error::FilsonResult;
-use crate::types::Op;
-use crate::{Appliable, DataNode, Extractable, FilsonError};
+use crate::types::{Op, Pattern, ValueType};
+use crate::{Appliable, Clock, DataNode, Extractable, FilsonError, FilterOptions, Params};
 use cfg_if::cfg_if;
 
 cfg_if! {
     if #[cfg(feature = "extraction_caching")] {
         use crate::ast::extraction_utils_cached as extraction_utils;
         use fxhash::FxHashMap;
-        use std::ptr::{addr_of_mut, NonNull};
+        use std::cell::RefCell;
     } else {
         use crate::ast::extraction_utils_uncached as extraction_utils;
     }
@@ -17,43 +23,274 @@ use extraction_utils::{get_extractable, CacheType};
 
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum Ast<'a> {
-    And(Box<Ast<'a>>, Box<Ast<'a>>),
-    Or(Box<Ast<'a>>, Box<Ast<'a>>),
-    Xor(Box<Ast<'a>>, Box<Ast<'a>>),
+    /// All of the operands are true, evaluated in order.
+    And(Vec<Ast<'a>>),
+    /// At least one of the operands is true, evaluated in order.
+    Or(Vec<Ast<'a>>),
+    /// Exactly one of the operands is true, evaluated in order.
+    Xor(Vec<Ast<'a>>),
     Not(Box<Ast<'a>>),
     Compare {
-        lhs: &'a str,
+        lhs: Subject<'a>,
         op: Op,
-        rhs: DataNode<'a>,
+        rhs: Operand<'a>,
+    },
+    Between {
+        lhs: Subject<'a>,
+        lower: Operand<'a>,
+        upper: Operand<'a>,
+        lower_inclusive: bool,
+        upper_inclusive: bool,
     },
     Intersects {
-        lhs: &'a str,
-        rhs: DataNode<'a>,
+        lhs: Subject<'a>,
+        rhs: Operand<'a>,
     },
     IsContained {
-        lhs: &'a str,
-        rhs: DataNode<'a>,
+        lhs: Subject<'a>,
+        rhs: Operand<'a>,
     },
     Exists {
-        path: &'a str,
+        path: DataPath<'a>,
+    },
+    IsType {
+        lhs: Subject<'a>,
+        types: Vec<ValueType>,
     },
     IsSubset {
-        lhs: &'a str,
-        rhs: DataNode<'a>,
+        lhs: Subject<'a>,
+        rhs: Operand<'a>,
     },
     IsSuperset {
-        lhs: &'a str,
-        rhs: DataNode<'a>,
+        lhs: Subject<'a>,
+        rhs: Operand<'a>,
+    },
+    Matches {
+        lhs: Subject<'a>,
+        rhs: Pattern,
+    },
+    // `rhs` of the case insensitive string predicates is lowercased when the filter is built
+    StartsWith {
+        lhs: Subject<'a>,
+        rhs: Cow<'a, str>,
+        case_insensitive: bool,
+    },
+    EndsWith {
+        lhs: Subject<'a>,
+        rhs: Cow<'a, str>,
+        case_insensitive: bool,
+    },
+    ContainsStr {
+        lhs: Subject<'a>,
+        rhs: Cow<'a, str>,
+        case_insensitive: bool,
+    },
+    // the condition of the quantifiers is evaluated against each of the elements of the collection in `path`,
+    // with the paths relative to the element
+    /// The condition holds for at least one of the elements, evaluated in order.
+    Any {
+        path: DataPath<'a>,
+        condition: Box<Ast<'a>>,
+    },
+    /// The condition holds for every element, evaluated in order.
+    All {
+        path: DataPath<'a>,
+        condition: Box<Ast<'a>>,
     },
+    /// The condition holds for none of the elements, evaluated in order.
+    None {
+        path: DataPath<'a>,
+        condition: Box<Ast<'a>>,
+    },
+    /// Number of the elements the condition holds for satisfies `op` against `rhs`.
+    Count {
+        path: DataPath<'a>,
+        condition: Box<Ast<'a>>,
+        op: Op,
+        rhs: DataNode<'static>,
+    },
+}
+
+/// Ast along with the names of its parameters, that are collected once, when the filter is built.
+#[derive(Debug)]
+pub(crate) struct ParsedAst<'a> {
+    ast: Ast<'a>,
+    params: Vec<String>,
+}
+
+impl<'a> From<Ast<'a>> for ParsedAst<'a> {
+    fn from(ast: Ast<'a>) -> Self {
+        let params = ast.params().into_iter().map(str::to_owned).collect();
+        Self { ast, params }
+    }
 }
 
-impl Appliable for Ast<'_> {
-    fn apply<T: Extractable>(&self, extractable: &T) -> FilsonResult<bool> {
+impl Appliable for ParsedAst<'_> {
+    fn apply_with_params_and_clock<T: Extractable, C: Clock>(
+        &self,
+        extractable: &T,
+        params: &Params,
+        clock: &C,
+    ) -> FilsonResult<bool> {
+        params.check_bound(self.params.iter().map(String::as_str))?;
+        let ctx = Context::new(clock, FilterOptions::default(), params);
+        self.ast.evaluate(extractable, &ctx)
+    }
+}
+
+impl Ast<'_> {
+    /// Converts the ast into one that owns all of its strings, detaching it from the input it was parsed from.
+    pub(crate) fn into_owned(self) -> Ast<'static> {
+        let all = |asts: Vec<Ast>| asts.into_iter().map(Ast::into_owned).collect();
+        let owned = |s: Cow<str>| -> Cow<'static, str> { Cow::Owned(s.into_owned()) };
+        match self {
+            Ast::And(asts) => Ast::And(all(asts)),
+            Ast::Or(asts) => Ast::Or(all(asts)),
+            Ast::Xor(asts) => Ast::Xor(all(asts)),
+            Ast::Not(ast) => Ast::Not(Box::new(ast.into_owned())),
+            Ast::Compare { lhs, op, rhs } => Ast::Compare {
+                lhs: lhs.into_owned(),
+                op,
+                rhs: rhs.into_owned(),
+            },
+            Ast::Between {
+                lhs,
+                lower,
+                upper,
+                lower_inclusive,
+                upper_inclusive,
+            } => Ast::Between {
+                lhs: lhs.into_owned(),
+                lower: lower.into_owned(),
+                upper: upper.into_owned(),
+                lower_inclusive,
+                upper_inclusive,
+            },
+            Ast::Intersects { lhs, rhs } => Ast::Intersects {
+                lhs: lhs.into_owned(),
+                rhs: rhs.into_owned(),
+            },
+            Ast::IsContained { lhs, rhs } => Ast::IsContained {
+                lhs: lhs.into_owned(),
+                rhs: rhs.into_owned(),
+            },
+            Ast::Exists { path } => Ast::Exists {
+                path: path.into_owned(),
+            },
+            Ast::IsType { lhs, types } => Ast::IsType {
+                lhs: lhs.into_owned(),
+                types,
+            },
+            Ast::IsSubset { lhs, rhs } => Ast::IsSubset {
+                lhs: lhs.into_owned(),
+                rhs: rhs.into_owned(),
+            },
+            Ast::IsSuperset { lhs, rhs } => Ast::IsSuperset {
+                lhs: lhs.into_owned(),
+                rhs: rhs.into_owned(),
+            },
+            Ast::Matches { lhs, rhs } => Ast::Matches {
+                lhs: lhs.into_owned(),
+                rhs,
+            },
+            Ast::StartsWith {
+                lhs,
+                rhs,
+                case_insensitive,
+            } => Ast::StartsWith {
+                lhs: lhs.into_owned(),
+                rhs: owned(rhs),
+                case_insensitive,
+            },
+            Ast::EndsWith {
+                lhs,
+                rhs,
+                case_insensitive,
+            } => Ast::EndsWith {
+                lhs: lhs.into_owned(),
+                rhs: owned(rhs),
+                case_insensitive,
+            },
+            Ast::ContainsStr {
+                lhs,
+                rhs,
+                case_insensitive,
+            } => Ast::ContainsStr {
+                lhs: lhs.into_owned(),
+                rhs: owned(rhs),
+                case_insensitive,
+            },
+            Ast::Any { path, condition } => Ast::Any {
+                path: path.into_owned(),
+                condition: Box::new(condition.into_owned()),
+            },
+            Ast::All { path, condition } => Ast::All {
+                path: path.into_owned(),
+                condition: Box::new(condition.into_owned()),
+            },
+            Ast::None { path, condition } => Ast::None {
+                path: path.into_owned(),
+                condition: Box::new(condition.into_owned()),
+            },
+            Ast::Count {
+                path,
+                condition,
+                op,
+                rhs,
+            } => Ast::Count {
+                path: path.into_owned(),
+                condition: Box::new(condition.into_owned()),
+                op,
+                rhs,
+            },
+        }
+    }
+
+    /// Names of the parameters in the condition, sorted and without duplicates.
+    pub(crate) fn params(&self) -> BTreeSet<&str> {
+        fn collect<'s>(ast: &'s Ast, names: &mut BTreeSet<&'s str>) {
+            let mut operands = |operands: &[&'s Operand]| {
+                names.extend(operands.iter().filter_map(|operand| operand.param_name()))
+            };
+            match ast {
+                Ast::And(asts) | Ast::Or(asts) | Ast::Xor(asts) => {
+                    asts.iter().for_each(|ast| collect(ast, names))
+                }
+                Ast::Not(ast)
+                | Ast::Any { condition: ast, .. }
+                | Ast::All { condition: ast, .. }
+                | Ast::None { condition: ast, .. }
+                | Ast::Count { condition: ast, .. } => collect(ast, names),
+                Ast::Compare { rhs, .. }
+                | Ast::Intersects { rhs, .. }
+                | Ast::IsContained { rhs, .. }
+                | Ast::IsSubset { rhs, .. }
+                | Ast::IsSuperset { rhs, .. } => operands(&[rhs]),
+                Ast::Between { lower, upper, .. } => operands(&[lower, upper]),
+                Ast::Exists { .. }
+                | Ast::IsType { .. }
+                | Ast::Matches { .. }
+                | Ast::StartsWith { .. }
+                | Ast::EndsWith { .. }
+                | Ast::ContainsStr { .. } => (),
+            }
+        }
+        let mut names = BTreeSet::new();
+        collect(self, &mut names);
+        names
+    }
+}
+
+impl Ast<'_> {
+    pub(crate) fn evaluate<T: Extractable>(
+        &self,
+        extractable: &T,
+        ctx: &Context,
+    ) -> FilsonResult<bool> {
         cfg_if! {
             if #[cfg(feature = "extraction_caching")] {
-                let mut cache_map = FxHashMap::default();
-                let cache =
-                    Some(NonNull::new(addr_of_mut!(cache_map)).ok_or(FilsonError::CacheCreationError)?);
+                let cache_map = RefCell::new(FxHashMap::default());
+                let cache = Some(&cache_map);
             } else {
                 let cache: Option<CacheType> = None;
             }
@@ -63,59 +300,258 @@ impl Appliable for Ast<'_> {
             ast: &'a Ast<'a>,
             extractable: &'a T,
             cache: Option<CacheType<'a>>,
+            ctx: &Context<'a>,
         ) -> FilsonResult<bool> {
+            let extract = |path| get_extractable(path, extractable, cache);
+            // references have to refer to a single value
+            let extract_one = |path| single(extract(path)?);
             let res = match ast {
-                Ast::And(lhs, rhs) => {
-                    recursive_apply(lhs, extractable, cache)?
-                        & recursive_apply(rhs, extractable, cache)?
+                // operands are evaluated in order and evaluation stops as soon as the result is decided,
+                // so the rest of the operands are neither extracted nor evaluated (and can't fail the filter)
+                Ast::And(operands) => {
+                    for operand in operands {
+                        if !recursive_apply(operand, extractable, cache, ctx)? {
+                            return Ok(false);
+                        }
+                    }
+                    true
                 }
-                Ast::Or(lhs, rhs) => {
-                    recursive_apply(lhs, extractable, cache)?
-                        | recursive_apply(rhs, extractable, cache)?
+                Ast::Or(operands) => {
+                    for operand in operands {
+                        if recursive_apply(operand, extractable, cache, ctx)? {
+                            return Ok(true);
+                        }
+                    }
+                    false
                 }
-                Ast::Xor(lhs, rhs) => {
-                    recursive_apply(lhs, extractable, cache)?
-                        ^ recursive_apply(rhs, extractable, cache)?
+                Ast::Xor(operands) => {
+                    let mut found = false;
+                    for operand in operands {
+                        if recursive_apply(operand, extractable, cache, ctx)? {
+                            if found {
+                                return Ok(false);
+                            }
+                            found = true;
+                        }
+                    }
+                    found
                 }
-                Ast::Not(inner) => !inner.apply(extractable)?,
+                Ast::Not(inner) => !recursive_apply(inner, extractable, cache, ctx)?,
                 Ast::Compare { lhs, op, rhs } => {
-                    let extracted = get_extractable(lhs, extractable, cache)?;
-                    extracted.error_on_type_mismatch(rhs)?;
-                    #[cfg(not(feature = "collections_ordering"))]
-                    if extracted.is_collection_type() & op.is_ordering() {
-                        return Err(FilsonError::OrderingProhibitedError);
-                    }
-                    extracted.compare(*op, rhs)
+                    let rhs = rhs.resolve(ctx, extract_one)?;
+                    any_value(lhs.evaluate(extract)?, |extracted| {
+                        compare(extracted, *op, &rhs, ctx)
+                    })?
+                }
+                Ast::Between {
+                    lhs,
+                    lower,
+                    upper,
+                    lower_inclusive,
+                    upper_inclusive,
+                } => {
+                    let (lower, upper) = (
+                        lower.resolve(ctx, extract_one)?,
+                        upper.resolve(ctx, extract_one)?,
+                    );
+                    let lower_op = if *lower_inclusive { Op::Gte } else { Op::Gt };
+                    let upper_op = if *upper_inclusive { Op::Lte } else { Op::Lt };
+                    any_value(lhs.evaluate(extract)?, |extracted| {
+                        // both of the bounds are checked, so mismatched types fail regardless of the value
+                        let above_lower = compare(extracted, lower_op, &lower, ctx)?;
+                        let below_upper = compare(extracted, upper_op, &upper, ctx)?;
+                        Ok(above_lower && below_upper)
+                    })?
                 }
                 Ast::Intersects { lhs, rhs } => {
-                    let extracted = get_extractable(lhs, extractable, cache)?;
-                    extracted.error_on_not_collection_or_string(FilsonError::IntersectsError)?;
-                    extracted.error_on_type_mismatch(rhs)?;
-                    extracted.intersects(rhs)
+                    let rhs = rhs.resolve(ctx, extract_one)?;
+                    let rhs = normalize_numbers(&rhs, ctx);
+                    any_value(lhs.evaluate(extract)?, |extracted| {
+                        let extracted = normalize_numbers(extracted, ctx);
+                        extracted
+                            .error_on_not_collection_or_string(FilsonError::IntersectsError)?;
+                        extracted.error_on_type_mismatch(&rhs)?;
+                        Ok(extracted.intersects(&rhs))
+                    })?
                 }
                 Ast::IsContained { lhs, rhs } => {
-                    let extracted = get_extractable(lhs, extractable, cache)?;
-                    rhs.contains(&extracted)
-                }
-                Ast::Exists { path } => {
-                    let extracted = get_extractable(path, extractable, cache);
-                    extracted.is_ok()
+                    let rhs = rhs.resolve(ctx, extract_one)?;
+                    let rhs = normalize_numbers(&rhs, ctx);
+                    let values = lhs.evaluate(extract)?;
+                    // only a reference can be anything else than a collection
+                    if !rhs.is_collection_type() {
+                        return Err(FilsonError::TypeError);
+                    }
+                    any_value(values, |extracted| {
+                        Ok(rhs.contains(&normalize_numbers(extracted, ctx)))
+                    })?
                 }
+                Ast::Exists { path } => matches!(extract(path), Ok(values) if !values.is_empty()),
+                Ast::IsType { lhs, types } => any_value(lhs.evaluate(extract)?, |extracted| {
+                    Ok(types.iter().any(|t| t.is_type_of(extracted)))
+                })?,
                 Ast::IsSubset { lhs, rhs } => {
-                    let extracted = get_extractable(lhs, extractable, cache)?;
-                    extracted.error_on_not_collection_or_string(FilsonError::IsSubsetError)?;
-                    extracted.error_on_type_mismatch(rhs)?;
-                    extracted.is_subset(rhs)
+                    let rhs = rhs.resolve(ctx, extract_one)?;
+                    let rhs = normalize_numbers(&rhs, ctx);
+                    any_value(lhs.evaluate(extract)?, |extracted| {
+                        let extracted = normalize_numbers(extracted, ctx);
+                        extracted.error_on_not_collection_or_string(FilsonError::IsSubsetError)?;
+                        extracted.error_on_type_mismatch(&rhs)?;
+                        Ok(extracted.is_subset(&rhs))
+                    })?
                 }
                 Ast::IsSuperset { lhs, rhs } => {
-                    let extracted = get_extractable(lhs, extractable, cache)?;
-                    extracted.error_on_not_collection_or_string(FilsonError::IsSupersetError)?;
-                    extracted.error_on_type_mismatch(rhs)?;
-                    extracted.is_superset(rhs)
+                    let rhs = rhs.resolve(ctx, extract_one)?;
+                    let rhs = normalize_numbers(&rhs, ctx);
+                    any_value(lhs.evaluate(extract)?, |extracted| {
+                        let extracted = normalize_numbers(extracted, ctx);
+                        extracted
+                            .error_on_not_collection_or_string(FilsonError::IsSupersetError)?;
+                        extracted.error_on_type_mismatch(&rhs)?;
+                        Ok(extracted.is_superset(&rhs))
+                    })?
+                }
+                Ast::Matches { lhs, rhs } => any_value(lhs.evaluate(extract)?, |extracted| {
+                    let haystack = extracted.as_str().ok_or(FilsonError::MatchesError)?;
+                    Ok(rhs.is_match(haystack))
+                })?,
+                Ast::StartsWith {
+                    lhs,
+                    rhs,
+                    case_insensitive,
+                } => any_value(lhs.evaluate(extract)?, |extracted| {
+                    let haystack = extracted.as_str().ok_or(FilsonError::StartsWithError)?;
+                    Ok(fold_case(haystack, *case_insensitive).starts_with(rhs.as_ref()))
+                })?,
+                Ast::EndsWith {
+                    lhs,
+                    rhs,
+                    case_insensitive,
+                } => any_value(lhs.evaluate(extract)?, |extracted| {
+                    let haystack = extracted.as_str().ok_or(FilsonError::EndsWithError)?;
+                    Ok(fold_case(haystack, *case_insensitive).ends_with(rhs.as_ref()))
+                })?,
+                Ast::ContainsStr {
+                    lhs,
+                    rhs,
+                    case_insensitive,
+                } => any_value(lhs.evaluate(extract)?, |extracted| {
+                    let haystack = extracted.as_str().ok_or(FilsonError::ContainsStrError)?;
+                    Ok(fold_case(haystack, *case_insensitive).contains(rhs.as_ref()))
+                })?,
+                Ast::Any { path, condition } => {
+                    let extracted = extract(path)?;
+                    for element in elements(&extracted)? {
+                        if condition.evaluate(element, ctx)? {
+                            return Ok(true);
+                        }
+                    }
+                    false
+                }
+                Ast::All { path, condition } => {
+                    let extracted = extract(path)?;
+                    for element in elements(&extracted)? {
+                        if !condition.evaluate(element, ctx)? {
+                            return Ok(false);
+                        }
+                    }
+                    true
+                }
+                Ast::None { path, condition } => {
+                    let extracted = extract(path)?;
+                    for element in elements(&extracted)? {
+                        if condition.evaluate(element, ctx)? {
+                            return Ok(false);
+                        }
+                    }
+                    true
+                }
+                Ast::Count {
+                    path,
+                    condition,
+                    op,
+                    rhs,
+                } => {
+                    let extracted = extract(path)?;
+                    let mut count = 0_usize;
+                    for element in elements(&extracted)? {
+                        if condition.evaluate(element, ctx)? {
+                            count += 1;
+                        }
+                    }
+                    op.is_satisfied_by(DataNode::from(count).cmp(rhs))
                 }
             };
             Ok(res)
         }
-        recursive_apply(self, extractable, cache)
+        recursive_apply(self, extractable, cache, ctx)
+    }
+}
+
+fn compare(extracted: &DataNode, op: Op, rhs: &DataNode, ctx: &Context) -> FilsonResult<bool> {
+    let coerced = extracted.coerce_to_type_of(rhs);
+    let extracted = normalize_numbers(coerced.as_ref().unwrap_or(extracted), ctx);
+    let rhs = normalize_numbers(rhs, ctx);
+    if ctx.options().numeric_coercion {
+        if let Some(ordering) = extracted.cmp_numerically(&rhs) {
+            return Ok(op.is_satisfied_by(ordering));
+        }
+    }
+    extracted.error_on_type_mismatch(&rhs)?;
+    #[cfg(not(feature = "collection_ordering"))]
+    if extracted.is_collection_type() & op.is_ordering() {
+        return Err(FilsonError::OrderingProhibitedError);
+    }
+    Ok(extracted.compare(op, &rhs))
+}
+
+/// Elements the condition of a quantifier is evaluated against, only arrays and sets can be quantified over.
+/// When the path refers to many collections, their elements are chained.
+fn elements<'n, 'a>(
+    nodes: &'n [Cow<'n, DataNode<'a>>],
+) -> FilsonResult<impl Iterator<Item = &'n DataNode<'a>>> {
+    let mut elements: Vec<Box<dyn Iterator<Item = &'n DataNode<'a>> + 'n>> = Vec::new();
+    for node in nodes {
+        match node.as_ref() {
+            DataNode::Array(arr) => elements.push(Box::new(arr.iter())),
+            DataNode::Set(set) => elements.push(Box::new(set.iter())),
+            _ => return Err(FilsonError::TypeError),
+        }
+    }
+    Ok(elements.into_iter().flatten())
+}
+
+/// An actor holds if it holds for any of the values of its left hand side.
+/// The values are checked in order, until the first one the actor holds for.
+fn any_value<'a>(
+    values: Vec<Cow<DataNode<'a>>>,
+    mut check: impl FnMut(&DataNode<'a>) -> FilsonResult<bool>,
+) -> FilsonResult<bool> {
+    for value in &values {
+        if check(value)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn single<'n, 'a>(values: Vec<Cow<'n, DataNode<'a>>>) -> FilsonResult<Cow<'n, DataNode<'a>>> {
+    let mut values = values.into_iter();
+    match (values.next(), values.next()) {
+        (Some(value), None) => Ok(value),
+        (None, _) => Err(FilsonError::ExtractionError),
+        (Some(_), Some(_)) => Err(FilsonError::MultipleValuesError),
+    }
+}
+
+/// Converts integral floats into integers, when numeric coercion is enabled,
+/// so they are equal to the integers of the same value, including the ones inside of collections.
+fn normalize_numbers<'n, 'a>(node: &'n DataNode<'a>, ctx: &Context) -> Cow<'n, DataNode<'a>> {
+    if !ctx.options().numeric_coercion {
+        return Cow::Borrowed(node);
+    }
+    match node.normalize_numbers() {
+        Some(normalized) => Cow::Owned(normalized),
+        None => Cow::Borrowed(node),
     }
 }
@@ -0,0 +1,50 @@
+use std::cell::Cell;
+
+use chrono::{DateTime, Utc};
+
+use crate::{Clock, DataNode, FilsonError, FilsonResult, FilterOptions, Params};
+
+/// State shared by all of the nodes during a single evaluation of the filter.
+pub(crate) struct Context<'c> {
+    clock: &'c dyn Clock,
+    now: Cell<Option<DateTime<Utc>>>,
+    options: FilterOptions,
+    params: &'c Params<'c>,
+}
+
+impl<'c> Context<'c> {
+    pub(crate) fn new(
+        clock: &'c dyn Clock,
+        options: FilterOptions,
+        params: &'c Params<'c>,
+    ) -> Self {
+        Self {
+            clock,
+            now: Cell::new(None),
+            options,
+            params,
+        }
+    }
+
+    pub(crate) fn options(&self) -> &FilterOptions {
+        &self.options
+    }
+
+    pub(crate) fn param(&self, name: &str) -> FilsonResult<&'c DataNode<'c>> {
+        self.params
+            .get(name)
+            .ok_or_else(|| FilsonError::UnboundParameterError(name.to_owned()))
+    }
+
+    /// The clock is read at most once, so every `now()` in the condition refers to the same instant.
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        match self.now.get() {
+            Some(now) => now,
+            None => {
+                let now = self.clock.now();
+                self.now.set(Some(now));
+                now
+            }
+        }
+    }
+}
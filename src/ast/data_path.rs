@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+
+use pest::iterators::Pair;
+
+use crate::{
+    error::FilsonResult,
+    parser::{error_at, parse_string, Rule},
+    DataNode, Extractable, FilsonError, JsonPath, Path,
+};
+
+/// Path to the data the actors are applied to.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub(crate) enum DataPath<'a> {
+    /// Interpreted by the [Extractable](crate::Extractable) implementation, e.g. as a json pointer.
+    Str(Cow<'a, str>),
+    /// Parsed when the filter is built.
+    Native(Path<'a>),
+    /// String that starts with `$`, parsed when the filter is built.
+    Json(JsonPath),
+}
+
+impl DataPath<'_> {
+    pub(crate) fn into_owned(self) -> DataPath<'static> {
+        match self {
+            DataPath::Str(path) => DataPath::Str(Cow::Owned(path.into_owned())),
+            DataPath::Native(path) => DataPath::Native(path.into_owned()),
+            DataPath::Json(path) => DataPath::Json(path),
+        }
+    }
+
+    pub(crate) fn extract_from<'e>(
+        &self,
+        extractable: &'e impl Extractable,
+    ) -> FilsonResult<Vec<DataNode<'e>>> {
+        match self {
+            DataPath::Str(path) => extractable.extract_all(path),
+            DataPath::Native(path) => extractable.extract_path(path),
+            DataPath::Json(path) => extractable.extract_json_path(path),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for DataPath<'a> {
+    fn from(path: &'a str) -> Self {
+        Self::Str(path.into())
+    }
+}
+
+impl<'a> From<Path<'a>> for DataPath<'a> {
+    fn from(path: Path<'a>) -> Self {
+        Self::Native(path)
+    }
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for DataPath<'a> {
+    type Error = FilsonError;
+
+    fn try_from(pair: Pair<'a, Rule>) -> FilsonResult<Self> {
+        match pair.as_rule() {
+            Rule::string => {
+                let chars = pair.clone().into_inner().next().unwrap().as_span();
+                let path = parse_string(pair)?;
+                if !path.starts_with('$') {
+                    return Ok(DataPath::Str(path));
+                }
+                JsonPath::parse(&path).map(DataPath::Json).map_err(|err| {
+                    // positions within the path are known only if it has no escape sequences
+                    let span = match path {
+                        Cow::Borrowed(_) => chars.get(err.range).unwrap_or(chars),
+                        Cow::Owned(_) => chars,
+                    };
+                    error_at(span, format!("invalid JSONPath: {}", err.message))
+                })
+            }
+            Rule::path => Path::try_from(pair).map(DataPath::Native),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -1,10 +1,11 @@
-use crate::{DataNode, Extractable, FilsonResult};
+use crate::{ast::DataPath, DataNode, Extractable, FilsonResult};
 
 use fxhash::FxHashMap;
 
-use std::{collections::hash_map::Entry, error::Error, ptr::NonNull};
+use std::{borrow::Cow, cell::RefCell, collections::hash_map::Entry, error::Error, rc::Rc};
 
-pub(super) type CacheType<'a> = NonNull<FxHashMap<&'a str, DataNode<'a>>>;
+/// Extracted values are reference counted, so they don't move when the map grows.
+pub(super) type CacheType<'a> = &'a RefCell<FxHashMap<&'a DataPath<'a>, Rc<Vec<DataNode<'a>>>>>;
 
 pub(super) struct FalliableEntry<'a, K, V> {
     entry: Entry<'a, K, V>,
@@ -33,13 +34,17 @@ impl<'a, K, V> From<Entry<'a, K, V>> for FalliableEntry<'a, K, V> {
 
 #[inline]
 pub(super) fn get_extractable<'a>(
-    path: &'a str,
+    path: &'a DataPath<'a>,
     extractable: &'a impl Extractable,
     cache: Option<CacheType<'a>>,
-) -> FilsonResult<&'a mut DataNode<'a>> {
-    let cache_ptr = cache.expect("Expected a pointer to cache to be present");
-    unsafe {
-        FalliableEntry::from((*cache_ptr.as_ptr()).entry(path))
-            .or_try_insert_with(|| extractable.extract(path))
-    }
+) -> FilsonResult<Vec<Cow<'a, DataNode<'a>>>> {
+    let cache = cache.expect("Expected a cache to be present");
+    let values = Rc::clone(
+        FalliableEntry::from(cache.borrow_mut().entry(path))
+            .or_try_insert_with(|| path.extract_from(extractable).map(Rc::new))?,
+    );
+    // SAFETY: entries are neither removed nor replaced, so the values are kept alive by the cache,
+    // which is borrowed for 'a, and they are never mutated, since the cache only hands out shared references.
+    let values: &'a Vec<DataNode<'a>> = unsafe { &*Rc::as_ptr(&values) };
+    Ok(values.iter().map(Cow::Borrowed).collect())
 }
@@ -1,5 +1,5 @@
-use crate::{DataNode, Extractable, FilsonResult};
-use std::marker::PhantomData;
+use crate::{ast::DataPath, DataNode, Extractable, FilsonResult};
+use std::{borrow::Cow, marker::PhantomData};
 
 #[derive(Clone, Copy)]
 pub(super) struct Nothing<'a>(PhantomData<&'a Self>);
@@ -8,9 +8,10 @@ pub(super) type CacheType<'a> = Nothing<'a>;
 
 #[inline]
 pub(super) fn get_extractable<'a>(
-    path: &'a str,
+    path: &'a DataPath<'a>,
     extractable: &'a impl Extractable,
     _cache: Option<CacheType>,
-) -> FilsonResult<DataNode<'a>> {
-    extractable.extract(path)
+) -> FilsonResult<Vec<Cow<'a, DataNode<'a>>>> {
+    let values = path.extract_from(extractable)?;
+    Ok(values.into_iter().map(Cow::Owned).collect())
 }
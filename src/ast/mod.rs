@@ -1,4 +1,9 @@
+#[allow(clippy::module_inception)]
 mod ast;
+mod context;
+mod data_path;
+mod operand;
+mod subject;
 
 use cfg_if::cfg_if;
 
@@ -10,4 +15,8 @@ cfg_if! {
     }
 }
 
-pub(crate) use ast::Ast;
+pub(crate) use ast::{Ast, ParsedAst};
+pub(crate) use context::Context;
+pub(crate) use data_path::DataPath;
+pub(crate) use operand::Operand;
+pub(crate) use subject::Subject;
@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+
+use chrono::Duration;
+use pest::iterators::Pair;
+
+use crate::{
+    ast::{Context, DataPath},
+    error::FilsonResult,
+    parser::{parse_duration_literal, Rule},
+    DataNode, FilsonError,
+};
+
+/// Right hand side of the actors, that may depend on the evaluation context or the data itself.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Operand<'a> {
+    Literal(DataNode<'a>),
+    /// `now()` shifted by the offset.
+    Now(Duration),
+    /// Value by the path in the same data the filter is applied to.
+    Path(DataPath<'a>),
+    /// Value bound to the name when the filter is applied.
+    Param(Cow<'a, str>),
+}
+
+impl<'a> Operand<'a> {
+    /// `extract` fetches the value by the path from the data the filter is applied to.
+    pub(crate) fn resolve<'r>(
+        &'r self,
+        ctx: &Context<'a>,
+        extract: impl FnOnce(&'r DataPath<'a>) -> FilsonResult<Cow<'r, DataNode<'a>>>,
+    ) -> FilsonResult<Cow<'r, DataNode<'a>>> {
+        match self {
+            Operand::Literal(node) => Ok(Cow::Borrowed(node)),
+            Operand::Path(path) => extract(path),
+            Operand::Param(name) => ctx.param(name).map(Cow::Borrowed),
+            Operand::Now(offset) => ctx
+                .now()
+                .checked_add_signed(*offset)
+                .map(|now| Cow::Owned(now.into()))
+                .ok_or(FilsonError::OverflowError),
+        }
+    }
+}
+
+impl Operand<'_> {
+    pub(crate) fn into_owned(self) -> Operand<'static> {
+        match self {
+            Operand::Literal(node) => Operand::Literal(node.into_owned()),
+            Operand::Now(offset) => Operand::Now(offset),
+            Operand::Path(path) => Operand::Path(path.into_owned()),
+            Operand::Param(name) => Operand::Param(Cow::Owned(name.into_owned())),
+        }
+    }
+
+    pub(crate) fn param_name(&self) -> Option<&str> {
+        match self {
+            Operand::Param(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T: Into<DataNode<'a>>> From<T> for Operand<'a> {
+    fn from(node: T) -> Self {
+        Self::Literal(node.into())
+    }
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for Operand<'a> {
+    type Error = FilsonError;
+
+    fn try_from(pair: Pair<'a, Rule>) -> FilsonResult<Self> {
+        match pair.as_rule() {
+            Rule::relative_time => (),
+            Rule::parameter => {
+                return Ok(Operand::Param(
+                    pair.into_inner().next().unwrap().as_str().into(),
+                ))
+            }
+            Rule::reference => {
+                return DataPath::try_from(pair.into_inner().next().unwrap()).map(Operand::Path)
+            }
+            _ => return DataNode::try_from(pair).map(Operand::Literal),
+        }
+        let mut inner = pair.into_inner().skip(1);
+        let offset = match (inner.next(), inner.next()) {
+            (Some(op), Some(duration)) => {
+                let duration = parse_duration_literal(duration)?;
+                if op.as_str() == "-" {
+                    -duration
+                } else {
+                    duration
+                }
+            }
+            _ => Duration::zero(),
+        };
+        Ok(Operand::Now(offset))
+    }
+}
@@ -0,0 +1,184 @@
+use std::borrow::Cow;
+
+use pest::{
+    iterators::Pair,
+    pratt_parser::{Assoc, Op as PrattOp, PrattParser},
+    Span,
+};
+
+use crate::{
+    ast::DataPath,
+    error::FilsonResult,
+    parser::{error_at, Rule},
+    types::{ArithmeticOp, Function, ValueType},
+    DataNode, FilsonError,
+};
+
+thread_local! {
+    // lowest precedence first
+    static ARITHMETIC_PRATT: PrattParser<Rule> = PrattParser::new()
+        .op(PrattOp::infix(Rule::add_op, Assoc::Left) | PrattOp::infix(Rule::sub_op, Assoc::Left))
+        .op(PrattOp::infix(Rule::mul_op, Assoc::Left)
+            | PrattOp::infix(Rule::div_op, Assoc::Left)
+            | PrattOp::infix(Rule::rem_op, Assoc::Left))
+        .op(PrattOp::prefix(Rule::neg));
+}
+
+/// Left hand side of the actors, the value that is extracted from the data.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Subject<'a> {
+    Path(DataPath<'a>),
+    Function {
+        function: Function,
+        arg: Box<Subject<'a>>,
+    },
+    /// Number in an arithmetic expression.
+    Literal(DataNode<'a>),
+    Neg(Box<Subject<'a>>),
+    Arithmetic {
+        op: ArithmeticOp,
+        lhs: Box<Subject<'a>>,
+        rhs: Box<Subject<'a>>,
+    },
+}
+
+impl<'a> Subject<'a> {
+    /// `extract` fetches the values by the path from the data the filter is applied to.
+    ///
+    /// A path may refer to many values, so does the subject then. Functions are applied to each of them,
+    /// and so is arithmetic, as long as the other operand is a single value. Arithmetic fails with
+    /// [FilsonError::MultipleValuesError] if both of its operands refer to many values, since they can't be paired up.
+    pub(crate) fn evaluate<'r, F>(&'r self, extract: F) -> FilsonResult<Vec<Cow<'r, DataNode<'a>>>>
+    where
+        F: Fn(&'r DataPath<'a>) -> FilsonResult<Vec<Cow<'r, DataNode<'a>>>> + Copy,
+    {
+        let nodes = match self {
+            Subject::Path(path) => return extract(path),
+            Subject::Literal(node) => return Ok(vec![Cow::Borrowed(node)]),
+            Subject::Function { function, arg } => arg
+                .evaluate(extract)?
+                .iter()
+                .map(|node| function.apply(node))
+                .collect::<FilsonResult<Vec<_>>>()?,
+            Subject::Neg(operand) => operand
+                .evaluate(extract)?
+                .iter()
+                .map(|node| node.negate())
+                .collect::<FilsonResult<Vec<_>>>()?,
+            Subject::Arithmetic { op, lhs, rhs } => {
+                let (lhs, rhs) = (lhs.evaluate(extract)?, rhs.evaluate(extract)?);
+                if lhs.len() > 1 && rhs.len() > 1 {
+                    return Err(FilsonError::MultipleValuesError);
+                }
+                let mut nodes = Vec::with_capacity(lhs.len() * rhs.len());
+                for lhs in &lhs {
+                    for rhs in &rhs {
+                        nodes.push(lhs.arithmetic(*op, rhs)?);
+                    }
+                }
+                nodes
+            }
+        };
+        Ok(nodes.into_iter().map(Cow::Owned).collect())
+    }
+
+    /// Type of the value, if it is known before the filter is applied.
+    pub(crate) fn value_type(&self) -> Option<ValueType> {
+        match self {
+            Subject::Path(_) => None,
+            Subject::Function { function, arg } => Some(function.output_type(arg.value_type())),
+            Subject::Literal(node) => Some(ValueType::of(node)),
+            Subject::Neg(_) | Subject::Arithmetic { .. } => Some(ValueType::Number),
+        }
+    }
+
+    /// Fails when the type of the value is known and isn't any of the `accepted` types.
+    pub(crate) fn expect_type(
+        &self,
+        span: Span,
+        accepted: &[ValueType],
+        context: &str,
+    ) -> FilsonResult<()> {
+        match self.value_type() {
+            Some(actual) if !accepted.iter().any(|t| t.overlaps(&actual)) => {
+                let accepted = accepted
+                    .iter()
+                    .map(ValueType::name)
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                Err(error_at(
+                    span,
+                    format!("{context} expects {accepted}, got {}", actual.name()),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Subject<'_> {
+    pub(crate) fn into_owned(self) -> Subject<'static> {
+        match self {
+            Subject::Path(path) => Subject::Path(path.into_owned()),
+            Subject::Function { function, arg } => Subject::Function {
+                function,
+                arg: Box::new(arg.into_owned()),
+            },
+            Subject::Literal(node) => Subject::Literal(node.into_owned()),
+            Subject::Neg(operand) => Subject::Neg(Box::new(operand.into_owned())),
+            Subject::Arithmetic { op, lhs, rhs } => Subject::Arithmetic {
+                op,
+                lhs: Box::new(lhs.into_owned()),
+                rhs: Box::new(rhs.into_owned()),
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Subject<'a> {
+    fn from(path: &'a str) -> Self {
+        Self::Path(path.into())
+    }
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for Subject<'a> {
+    type Error = FilsonError;
+
+    fn try_from(pair: Pair<'a, Rule>) -> FilsonResult<Self> {
+        match pair.as_rule() {
+            Rule::string | Rule::path => DataPath::try_from(pair).map(Subject::Path),
+            Rule::function => {
+                let mut inner = pair.into_inner();
+                let identifier = inner.next().unwrap().as_str();
+                let arg = inner.next().unwrap();
+                let arg_span = arg.as_span();
+                let arg = Subject::try_from(arg)?;
+                let function = Function::from(identifier);
+                arg.expect_type(arg_span, function.accepts(), identifier)?;
+                Ok(Subject::Function {
+                    function,
+                    arg: Box::new(arg),
+                })
+            }
+            Rule::arithmetic => ARITHMETIC_PRATT.with(|pratt| {
+                pratt
+                    .map_primary(|primary| {
+                        let span = primary.as_span();
+                        let operand = Subject::try_from(primary)?;
+                        operand.expect_type(span, &[ValueType::Number], "arithmetic")?;
+                        Ok(operand)
+                    })
+                    .map_prefix(|_, operand| Ok(Subject::Neg(Box::new(operand?))))
+                    .map_infix(|lhs, op, rhs| {
+                        Ok(Subject::Arithmetic {
+                            op: op.as_str().into(),
+                            lhs: Box::new(lhs?),
+                            rhs: Box::new(rhs?),
+                        })
+                    })
+                    .parse(pair.into_inner())
+            }),
+            _ => DataNode::try_from(pair).map(Subject::Literal),
+        }
+    }
+}
@@ -5,8 +5,12 @@ use thiserror::Error as ThisError;
 
 #[derive(Debug, Clone, ThisError, PartialEq, Eq)]
 pub enum FilsonError {
-    #[error(transparent)]
-    NumberParseError(#[from] LexicalError),
+    /// `span` is the byte range of the number literal within the input.
+    #[error("Invalid number literal at {}..{}: {source}", span.0, span.1)]
+    NumberParseError {
+        source: LexicalError,
+        span: (usize, usize),
+    },
 
     #[error(transparent)]
     ParseError(#[from] Box<PestError<Rule>>),
@@ -14,6 +18,15 @@ pub enum FilsonError {
     #[error("Data by specified path doesn't exist.")]
     ExtractionError,
 
+    #[error("Path refers to more than one value, where a single one is expected.")]
+    MultipleValuesError,
+
+    #[error("Invalid path: {0}")]
+    PathParseError(String),
+
+    #[error("Parameter ${0} isn't bound.")]
+    UnboundParameterError(String),
+
     #[error("Values are of different types.")]
     TypeError,
 
@@ -29,6 +42,27 @@ pub enum FilsonError {
     #[error("Can't check if superset, since extracted data isn't array/set/map/string.")]
     IsSupersetError,
 
+    #[error("Can't match against pattern, since extracted data isn't string.")]
+    MatchesError,
+
+    #[error("Can't check the prefix, since extracted data isn't string.")]
+    StartsWithError,
+
+    #[error("Can't check the suffix, since extracted data isn't string.")]
+    EndsWithError,
+
+    #[error("Can't check for substring, since extracted data isn't string.")]
+    ContainsStrError,
+
+    #[error("Arithmetic operation overflowed.")]
+    OverflowError,
+
+    #[error("Division by zero.")]
+    DivisionByZeroError,
+
+    #[error("Value can't be converted to the requested type.")]
+    ConversionError,
+
     #[cfg(feature = "extraction_caching")]
     #[error("Ptr to cache was null")]
     CacheCreationError,
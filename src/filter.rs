@@ -0,0 +1,151 @@
+use std::{fmt, str::FromStr, sync::Arc};
+
+use crate::{
+    ast::{Ast, Context},
+    parser::get_ast,
+    Appliable, Clock, Extractable, FilsonError, FilsonResult, Params,
+};
+
+/// An owned, compiled filtration condition.
+///
+/// Unlike the filter returned by [get_filter](crate::get_filter), [Filter] doesn't borrow the input it was built from.
+/// It has no lifetime, so it can be stored in structs, caches or global registries,
+/// and it is `Send + Sync`, so it can be shared across threads.
+///
+/// Cloning is cheap, since the compiled condition is shared between the clones.
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use std::{sync::Arc, thread};
+///
+/// use serde_json::json;
+/// use filson::{Appliable, Filter};
+///
+/// // Remember that this condition may be known only at runtime
+/// let cond = String::from(r#"compare("/num" == 1)"#);
+/// let flt: Filter = cond.parse().unwrap();
+/// // the filter outlives the input it was compiled from
+/// drop(cond);
+///
+/// let flt = Arc::new(flt);
+/// let handles = (0..2)
+///     .map(|i| {
+///         let flt = Arc::clone(&flt);
+///         thread::spawn(move || flt.apply(&json!({ "num": i })).unwrap())
+///     })
+///     .collect::<Vec<_>>();
+/// let res = handles
+///     .into_iter()
+///     .map(|handle| handle.join().unwrap())
+///     .collect::<Vec<_>>();
+/// assert_eq!(res, vec![false, true]);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Filter {
+    ast: Arc<Ast<'static>>,
+    source: Arc<str>,
+    options: FilterOptions,
+    params: Arc<[String]>,
+}
+
+/// Changes how a [Filter] is evaluated.
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use serde_json::json;
+/// use filson::{Appliable, Filter, FilterOptions};
+///
+/// let data = json!({"price": 10.5, "count": 1});
+///
+/// let flt = Filter::new(r#"compare("/price" > 10)"#).unwrap();
+/// assert!(flt.apply(&data).is_err());
+///
+/// let options = FilterOptions::default().numeric_coercion(true);
+/// let flt = Filter::with_options(r#"compare("/price" > 10)"#, options).unwrap();
+/// assert_eq!(flt.apply(&data), Ok(true));
+/// let flt = Filter::with_options(r#"is_contained("/count" [1.0, 2.0])"#, options).unwrap();
+/// assert_eq!(flt.apply(&data), Ok(true));
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FilterOptions {
+    pub(crate) numeric_coercion: bool,
+}
+
+impl FilterOptions {
+    /// Integers, floats and decimals are compared by their numeric value instead of failing with [FilsonError::TypeError],
+    /// in all of the actors, including the elements of collections. Disabled by default.
+    ///
+    /// Decimals are compared with floats as floats, the rest of the comparisons are exact.
+    pub fn numeric_coercion(mut self, enabled: bool) -> Self {
+        self.numeric_coercion = enabled;
+        self
+    }
+}
+
+impl Filter {
+    /// Compiles the filtration condition written in Filson syntax.
+    pub fn new(inp: &str) -> FilsonResult<Self> {
+        Self::with_options(inp, FilterOptions::default())
+    }
+
+    /// Compiles the filtration condition written in Filson syntax, that is evaluated according to the `options`.
+    pub fn with_options(inp: &str, options: FilterOptions) -> FilsonResult<Self> {
+        let ast = get_ast(inp)?.into_owned();
+        let params = ast.params().into_iter().map(str::to_owned).collect();
+        Ok(Self {
+            ast: Arc::new(ast),
+            source: Arc::from(inp),
+            options,
+            params,
+        })
+    }
+
+    /// The condition this filter was compiled from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The options this filter is evaluated according to.
+    pub fn options(&self) -> FilterOptions {
+        self.options
+    }
+
+    /// Names of the `$name` parameters in the condition, sorted and without duplicates.
+    /// All of them have to be bound in [Params], when the filter is applied.
+    pub fn params(&self) -> &[String] {
+        &self.params
+    }
+}
+
+impl Appliable for Filter {
+    fn apply_with_params_and_clock<T: Extractable, C: Clock>(
+        &self,
+        v: &T,
+        params: &Params,
+        clock: &C,
+    ) -> FilsonResult<bool> {
+        params.check_bound(self.params.iter().map(String::as_str))?;
+        self.ast
+            .evaluate(v, &Context::new(clock, self.options, params))
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Debug for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Filter")
+            .field("source", &self.source)
+            .field("options", &self.options)
+            .field("params", &self.params)
+            .finish()
+    }
+}
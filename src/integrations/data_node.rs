@@ -0,0 +1,80 @@
+use crate::{
+    error::FilsonResult,
+    path::{non_empty, pointer_child, Navigable, Path},
+    DataNode, Extractable, FilsonError, JsonPath,
+};
+
+/// Paths are [json pointers](https://www.rfc-editor.org/rfc/rfc6901) into maps with string keys and arrays,
+/// the empty path refers to the node itself.
+///
+/// This is how the condition of a quantifier reaches into each of the elements.
+impl Extractable for DataNode<'_> {
+    fn extract(&self, path: &str) -> FilsonResult<DataNode<'_>> {
+        if path.is_empty() {
+            return Ok(self.as_borrowed());
+        }
+        let tokens = path.strip_prefix('/').ok_or(FilsonError::ExtractionError)?;
+        let mut node = self;
+        for token in tokens.split('/') {
+            node = pointer_child(node, token).ok_or(FilsonError::ExtractionError)?;
+        }
+        Ok(node.as_borrowed())
+    }
+
+    /// Paths may have wildcards or be JSONPath as well, see [Navigable::select].
+    fn extract_all(&self, path: &str) -> FilsonResult<Vec<DataNode<'_>>> {
+        Ok(self
+            .select(path)?
+            .into_iter()
+            .map(DataNode::as_borrowed)
+            .collect())
+    }
+
+    fn extract_path(&self, path: &Path) -> FilsonResult<Vec<DataNode<'_>>> {
+        Ok(path
+            .select(self)?
+            .into_iter()
+            .map(DataNode::as_borrowed)
+            .collect())
+    }
+
+    fn extract_json_path(&self, path: &JsonPath) -> FilsonResult<Vec<DataNode<'_>>> {
+        Ok(non_empty(path.select(self))?
+            .into_iter()
+            .map(DataNode::as_borrowed)
+            .collect())
+    }
+}
+
+/// Only maps with string keys are objects, and sets have children, but no indices.
+impl<'a> Navigable for DataNode<'a> {
+    fn member(&self, name: &str) -> Option<&Self> {
+        match self {
+            DataNode::Map(map) => {
+                let key = DataNode::from(name);
+                // SAFETY: the key is only compared with the keys of the map during the lookup and isn't kept by it,
+                // so it may borrow the name for less than 'a, the map can't be searched otherwise without cloning it.
+                let key: &DataNode<'a> =
+                    unsafe { &*(&key as *const DataNode<'_>).cast::<DataNode<'a>>() };
+                map.get(key)
+            }
+            _ => None,
+        }
+    }
+
+    fn elements(&self) -> Option<&[Self]> {
+        match self {
+            DataNode::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        match self {
+            DataNode::Map(map) => map.values().collect(),
+            DataNode::Set(set) => set.iter().collect(),
+            DataNode::Array(arr) => arr.iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -1,2 +1,3 @@
+mod data_node;
 #[cfg(feature = "serde_json")]
 mod serde_value;
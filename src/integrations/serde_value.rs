@@ -1,4 +1,8 @@
-use crate::{error::FilsonResult, DataNode, Extractable, FilsonError};
+use crate::{
+    error::FilsonResult, path::non_empty, DataNode, Extractable, FilsonError, JsonPath, Navigable,
+    Path,
+};
+use cfg_if::cfg_if;
 use std::collections::BTreeMap;
 
 impl<'a> From<&'a serde_json::Value> for DataNode<'a> {
@@ -6,10 +10,12 @@ impl<'a> From<&'a serde_json::Value> for DataNode<'a> {
         match value {
             serde_json::Value::Bool(b) => (*b).into(),
             serde_json::Value::Number(x) => {
-                if x.is_i64() {
-                    x.as_i64().unwrap().into()
+                if let Some(i) = x.as_i64() {
+                    i.into()
+                } else if let Some(u) = x.as_u64() {
+                    u.into()
                 } else {
-                    x.as_f64().unwrap().into()
+                    non_64_bit_integer_number(x)
                 }
             }
             serde_json::Value::String(x) => x.as_str().into(),
@@ -24,10 +30,74 @@ impl<'a> From<&'a serde_json::Value> for DataNode<'a> {
     }
 }
 
+cfg_if! {
+    if #[cfg(feature = "arbitrary_precision")] {
+        use rust_decimal::Decimal;
+
+        /// Keeps numbers exact: integers that don't fit into 64 bits become 128 bit ones and the rest become decimals.
+        /// Falls back to float, when the number can't be represented exactly.
+        fn non_64_bit_integer_number(x: &serde_json::Number) -> DataNode<'static> {
+            let repr = x.to_string();
+            if let Ok(i) = repr.parse::<i128>() {
+                return i.into();
+            }
+            if let Ok(u) = repr.parse::<u128>() {
+                return u.into();
+            }
+            let decimal = if repr.contains(['e', 'E']) {
+                Decimal::from_scientific(&repr)
+            } else {
+                Decimal::from_str_exact(&repr)
+            };
+            decimal
+                .map(DataNode::from)
+                .unwrap_or_else(|_| x.as_f64().unwrap().into())
+        }
+    } else {
+        fn non_64_bit_integer_number(x: &serde_json::Number) -> DataNode<'static> {
+            x.as_f64().unwrap().into()
+        }
+    }
+}
+
 impl Extractable for serde_json::Value {
-    fn extract(&self, path: &str) -> FilsonResult<DataNode> {
+    fn extract(&self, path: &str) -> FilsonResult<DataNode<'_>> {
         self.pointer(path)
             .ok_or(FilsonError::ExtractionError)
-            .and_then(|v| Ok(DataNode::from(v)))
+            .map(DataNode::from)
+    }
+
+    /// Paths may have wildcards or be JSONPath as well, see [Navigable::select].
+    fn extract_all(&self, path: &str) -> FilsonResult<Vec<DataNode<'_>>> {
+        Ok(self.select(path)?.into_iter().map(DataNode::from).collect())
+    }
+
+    fn extract_path(&self, path: &Path) -> FilsonResult<Vec<DataNode<'_>>> {
+        Ok(path.select(self)?.into_iter().map(DataNode::from).collect())
+    }
+
+    fn extract_json_path(&self, path: &JsonPath) -> FilsonResult<Vec<DataNode<'_>>> {
+        Ok(non_empty(path.select(self))?
+            .into_iter()
+            .map(DataNode::from)
+            .collect())
+    }
+}
+
+impl Navigable for serde_json::Value {
+    fn member(&self, name: &str) -> Option<&Self> {
+        self.as_object()?.get(name)
+    }
+
+    fn elements(&self) -> Option<&[Self]> {
+        self.as_array().map(Vec::as_slice)
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        match self {
+            serde_json::Value::Array(arr) => arr.iter().collect(),
+            serde_json::Value::Object(obj) => obj.values().collect(),
+            _ => Vec::new(),
+        }
     }
 }
@@ -15,6 +15,7 @@
 //! # Quickstart
 //!
 //! ```rust
+//! # #[cfg(feature = "serde_json")] {
 //! use serde_json::json;
 //!
 //! use filson::{Appliable, get_filter};
@@ -31,18 +32,23 @@
 //!             .filter(|data_point| flt.apply(data_point).unwrap_or(false))
 //!             .collect::<Vec<_>>();
 //! assert_eq!(res, vec![json!({"num": 1})]);
+//! # }
 //! ```
 //!
 //! # The concepts
 //!
 //! [get_filter]
-//! This function is the single entry point.
+//! This function is the main entry point.
 //! It accepts an ```&str``` as input with the filtration condition written in Filson syntax(see syntax reference).
 //! This condition may be known only at runtime, say, recieved over the network or supplied as a command line argument.
 //! It builds the opaque [Appliable] object which knows how to apply the filtration over your data.
 //! Please note, that your data type has to implement [Extractable].
 //!
 //!
+//! [Filter] is the owned counterpart of whatever [get_filter] returns.
+//! It doesn't borrow the input, so it can be compiled once, stored and shared across threads for the life of the process.
+//!
+//!
 //! [DataNode] is the internal representation of data used by Filson.
 //! In order to run comparisons over your data it has to be converted during extraction. See [Extractable] for examples.
 //!
@@ -52,6 +58,15 @@
 //!
 //! [Extractable] is a triat that defines how to **extract** the data from any type that implements and convert it to [DataNode]. See [examples](Extractable).
 //!
+//!
+//! [Clock] is a trait that defines where `now()` in the filtration conditions is read from. See [examples](Clock).
+//!
+//!
+//! [Navigable] is a trait for tree-shaped data, that makes json pointers and [JsonPath] expressions work on it. See [examples](Navigable).
+//!
+//!
+//! [Params] are the values of the `$name` parameters in the filtration conditions, bound when the filter is applied. See [examples](Params).
+//!
 //! # Features
 //!
 //! - ## serde_json
@@ -62,7 +77,20 @@
 //!
 //! Enables integreation with [serde_json::Value] out of the box.
 //!
-//! - ## extractable_caching
+//! - ## arbitrary_precision
+//!
+//!
+//! **Disabled by default**
+//!
+//!
+//! Enables `arbitrary_precision` feature of [serde_json], so json numbers are extracted exactly:
+//! integers that don't fit into 64 bits become 128 bit integers and the rest of the non-integer numbers become decimals.
+//! Such numbers can't be compared against float literals then, use decimal literals instead (see language reference).
+//!
+//!
+//! **Carefull! The feature changes how [serde_json] parses numbers for the whole dependency graph!**
+//!
+//! - ## extraction_caching
 //!
 //!
 //! **Disabled by default**
@@ -74,7 +102,7 @@
 //!
 //! **Please, always benchmark first!**
 //!
-//! - ## collections_ordering
+//! - ## collection_ordering
 //!
 //!
 //! **Disabled by default**
@@ -88,17 +116,21 @@
 mod actors;
 mod ast;
 mod error;
+mod filter;
 mod integrations;
 mod parser;
+mod path;
 mod traits;
 mod types;
 
-use crate::parser::get_ast;
+use crate::{ast::ParsedAst, parser::get_ast};
 
 pub use error::{FilsonError, FilsonResult};
-pub use traits::{Appliable, Extractable};
-pub use types::DataNode;
+pub use filter::{Filter, FilterOptions};
+pub use path::{JsonPath, Navigable, Path, PathSegment};
+pub use traits::{Appliable, Clock, Extractable, SystemClock};
+pub use types::{DataNode, Params};
 
 pub fn get_filter(inp: &str) -> FilsonResult<impl Appliable + '_> {
-    get_ast(inp)
+    get_ast(inp).map(ParsedAst::from)
 }
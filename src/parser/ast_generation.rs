@@ -1,113 +1,311 @@
-use crate::{ast::Ast, error::FilsonResult};
-use pest::{iterators::Pair, Parser};
+use crate::{
+    ast::{Ast, Operand, Subject},
+    error::FilsonResult,
+    parser::{error_at, parse_int_literal, parse_string},
+    types::{Pattern, ValueType, PATTERN_FLAGS},
+};
+use pest::{
+    iterators::Pair,
+    pratt_parser::{Assoc, Op as PrattOp, PrattParser},
+    Parser,
+};
 
 #[derive(pest_derive::Parser)]
 #[grammar = "filson_grammar.pest"]
 pub(crate) struct FilsonParser;
 
+thread_local! {
+    // lowest precedence first
+    static PRATT: PrattParser<Rule> = PrattParser::new()
+        .op(PrattOp::infix(Rule::or_op, Assoc::Left))
+        .op(PrattOp::infix(Rule::xor_op, Assoc::Left))
+        .op(PrattOp::infix(Rule::and_op, Assoc::Left))
+        .op(PrattOp::prefix(Rule::not));
+}
+
 pub(crate) fn get_ast<'a>(inp: &'a str) -> FilsonResult<Ast<'a>> {
     let pair: Pair<'a, Rule> = match FilsonParser::parse(Rule::expression, inp) {
         Ok(mut pairs) => pairs.next().unwrap(),
         Err(pest_err) => Err(Box::new(pest_err))?,
     };
 
-    fn get_ast_recursively(pair: Pair<Rule>) -> Ast {
-        match pair.as_rule() {
+    fn get_ast_recursively<'a>(
+        pair: Pair<'a, Rule>,
+        pratt: &PrattParser<Rule>,
+    ) -> FilsonResult<Ast<'a>> {
+        let ast = match pair.as_rule() {
+            // the operator of the chain that built the node is kept along with it,
+            // so only the chains are flattened, not the groups in parentheses or the prefix form
+            Rule::condition => {
+                pratt
+                    .map_primary(|primary| -> FilsonResult<_> {
+                        Ok((get_ast_recursively(primary, pratt)?, None))
+                    })
+                    .map_prefix(|op, operand| match op.as_rule() {
+                        Rule::not => Ok((Ast::Not(Box::new(operand?.0)), None)),
+                        _ => unreachable!(),
+                    })
+                    .map_infix(|lhs, op, rhs| {
+                        let ((lhs, chain), (rhs, _)) = (lhs?, rhs?);
+                        let op = op.as_rule();
+                        // chains of operators are flattened into a single node, so they mean the same as the prefix form,
+                        // e.g. `a ^^ b ^^ c` is `xor(a, b, c)`, i.e. exactly one of the operands is true
+                        let ast = match (op, lhs) {
+                            (Rule::and_op, Ast::And(mut operands)) if chain == Some(op) => {
+                                operands.push(rhs);
+                                Ast::And(operands)
+                            }
+                            (Rule::and_op, lhs) => Ast::And(vec![lhs, rhs]),
+                            (Rule::or_op, Ast::Or(mut operands)) if chain == Some(op) => {
+                                operands.push(rhs);
+                                Ast::Or(operands)
+                            }
+                            (Rule::or_op, lhs) => Ast::Or(vec![lhs, rhs]),
+                            (Rule::xor_op, Ast::Xor(mut operands)) if chain == Some(op) => {
+                                operands.push(rhs);
+                                Ast::Xor(operands)
+                            }
+                            (Rule::xor_op, lhs) => Ast::Xor(vec![lhs, rhs]),
+                            _ => unreachable!(),
+                        };
+                        Ok((ast, Some(op)))
+                    })
+                    .parse(pair.into_inner())?
+                    .0
+            }
+
             Rule::compare => {
                 let mut inner = pair.into_inner();
-                let pointer = inner.next().unwrap().into_inner().next().unwrap();
+                let subject = inner.next().unwrap();
                 let op = inner.next().unwrap();
                 let comparee = inner.next().unwrap();
                 // TODO type check and split into compare eq and compare ord in here
+                let lhs = subject.try_into()?;
+                let rhs = parse_comparee(comparee, &lhs)?;
                 Ast::Compare {
-                    lhs: pointer.as_str(),
+                    lhs,
                     op: op.as_str().into(),
-                    rhs: comparee.into(),
+                    rhs,
+                }
+            }
+
+            Rule::between => {
+                let mut inner = pair.into_inner();
+                let subject = inner.next().unwrap();
+                let bounds = inner.next().unwrap();
+                let (lower_inclusive, lower, upper, upper_inclusive) =
+                    if bounds.as_rule() == Rule::interval {
+                        let mut interval = bounds.into_inner();
+                        let open = interval.next().unwrap();
+                        let lower = interval.next().unwrap();
+                        let upper = interval.next().unwrap();
+                        let close = interval.next().unwrap();
+                        (open.as_str() == "[", lower, upper, close.as_str() == "]")
+                    } else {
+                        (true, bounds, inner.next().unwrap(), true)
+                    };
+                let lhs = subject.try_into()?;
+                Ast::Between {
+                    lower: parse_comparee(lower, &lhs)?,
+                    upper: parse_comparee(upper, &lhs)?,
+                    lhs,
+                    lower_inclusive,
+                    upper_inclusive,
                 }
             }
 
             Rule::intersects => {
                 let mut inner = pair.into_inner();
-                let pointer = inner.next().unwrap().into_inner().next().unwrap();
+                let subject = inner.next().unwrap();
                 let compound_or_str = inner.next().unwrap();
                 Ast::Intersects {
-                    lhs: pointer.as_str(),
-                    rhs: compound_or_str.into(),
+                    lhs: parse_subject(subject, COLLECTION_OR_STRING, "intersects")?,
+                    rhs: compound_or_str.try_into()?,
                 }
             }
 
             Rule::is_contained => {
                 let mut inner = pair.into_inner();
-                let pointer = inner.next().unwrap().into_inner().next().unwrap();
+                let subject = inner.next().unwrap();
                 let compound = inner.next().unwrap();
                 Ast::IsContained {
-                    lhs: pointer.as_str(),
-                    rhs: compound.into(),
+                    lhs: subject.try_into()?,
+                    rhs: compound.try_into()?,
                 }
             }
 
             Rule::exists => {
                 let mut inner = pair.into_inner();
-                let pointer = inner.next().unwrap().into_inner().next().unwrap();
+                let pointer = inner.next().unwrap();
                 Ast::Exists {
-                    path: pointer.as_str(),
+                    path: pointer.try_into()?,
+                }
+            }
+
+            Rule::is_type => {
+                let mut inner = pair.into_inner();
+                let subject = inner.next().unwrap();
+                Ast::IsType {
+                    lhs: subject.try_into()?,
+                    types: inner.map(|t| ValueType::from(t.as_str())).collect(),
                 }
             }
 
             Rule::is_subset => {
                 let mut inner = pair.into_inner();
-                let pointer = inner.next().unwrap().into_inner().next().unwrap();
+                let subject = inner.next().unwrap();
                 let compound_or_str = inner.next().unwrap();
                 Ast::IsSubset {
-                    lhs: pointer.as_str(),
-                    rhs: compound_or_str.into(),
+                    lhs: parse_subject(subject, COLLECTION_OR_STRING, "is_subset")?,
+                    rhs: compound_or_str.try_into()?,
                 }
             }
 
             Rule::is_superset => {
                 let mut inner = pair.into_inner();
-                let pointer = inner.next().unwrap().into_inner().next().unwrap();
+                let subject = inner.next().unwrap();
                 let compound_or_str = inner.next().unwrap();
                 Ast::IsSuperset {
-                    lhs: pointer.as_str(),
-                    rhs: compound_or_str.into(),
+                    lhs: parse_subject(subject, COLLECTION_OR_STRING, "is_superset")?,
+                    rhs: compound_or_str.try_into()?,
+                }
+            }
+
+            Rule::matches => {
+                let mut inner = pair.into_inner();
+                let lhs = parse_subject(inner.next().unwrap(), &[ValueType::String], "matches")?;
+                let pattern = inner.next().unwrap();
+                let pattern_span = pattern.as_span();
+                let pattern = parse_string(pattern)?;
+                let flags = match inner.next() {
+                    Some(flags) => {
+                        let flags_span = flags.as_span();
+                        let flags = parse_string(flags)?;
+                        if let Some(flag) = flags.chars().find(|ch| !PATTERN_FLAGS.contains(*ch)) {
+                            return Err(error_at(
+                                flags_span,
+                                format!("unknown pattern flag '{flag}', expected any of \"{PATTERN_FLAGS}\""),
+                            ));
+                        }
+                        flags
+                    }
+                    None => "".into(),
+                };
+                Ast::Matches {
+                    lhs,
+                    rhs: Pattern::new(&pattern, &flags)
+                        .map_err(|err| error_at(pattern_span, format!("invalid pattern: {err}")))?,
+                }
+            }
+
+            Rule::string_predicate => {
+                let mut inner = pair.into_inner();
+                let identifier = inner.next().unwrap().as_str();
+                let lhs = parse_subject(inner.next().unwrap(), &[ValueType::String], identifier)?;
+                let rhs = parse_string(inner.next().unwrap())?;
+                let (case_insensitive, identifier) = match identifier.strip_prefix('i') {
+                    Some(identifier) => (true, identifier),
+                    None => (false, identifier),
+                };
+                let rhs = if case_insensitive {
+                    rhs.to_lowercase().into()
+                } else {
+                    rhs
+                };
+                match identifier {
+                    "starts_with" => Ast::StartsWith {
+                        lhs,
+                        rhs,
+                        case_insensitive,
+                    },
+                    "ends_with" => Ast::EndsWith {
+                        lhs,
+                        rhs,
+                        case_insensitive,
+                    },
+                    "contains_str" => Ast::ContainsStr {
+                        lhs,
+                        rhs,
+                        case_insensitive,
+                    },
+                    _ => unreachable!(),
                 }
             }
 
-            Rule::binary_operation => {
+            Rule::quantifier => {
                 let mut inner = pair.into_inner();
                 let identifier = inner.next().unwrap();
-                let body = inner.next().unwrap();
-                let mut body_inner = body.into_inner();
-                let left = body_inner.next().unwrap();
-                let right = body_inner.next().unwrap();
+                let path = inner.next().unwrap().try_into()?;
+                let condition = Box::new(get_ast_recursively(inner.next().unwrap(), pratt)?);
                 match identifier.as_str() {
-                    "and" => Ast::And(
-                        Box::new(get_ast_recursively(left)),
-                        Box::new(get_ast_recursively(right)),
-                    ),
-                    "or" => Ast::Or(
-                        Box::new(get_ast_recursively(left)),
-                        Box::new(get_ast_recursively(right)),
-                    ),
-                    "xor" => Ast::Xor(
-                        Box::new(get_ast_recursively(left)),
-                        Box::new(get_ast_recursively(right)),
-                    ),
+                    "any" => Ast::Any { path, condition },
+                    "all" => Ast::All { path, condition },
+                    "none" => Ast::None { path, condition },
                     _ => unreachable!(),
                 }
             }
 
-            Rule::not => {
+            Rule::count => {
+                let mut inner = pair.into_inner();
+                let path = inner.next().unwrap().try_into()?;
+                let condition = Box::new(get_ast_recursively(inner.next().unwrap(), pratt)?);
+                let op = inner.next().unwrap();
+                Ast::Count {
+                    path,
+                    condition,
+                    op: op.as_str().into(),
+                    rhs: parse_int_literal(inner.next().unwrap())?,
+                }
+            }
+
+            Rule::logical_operation => {
                 let mut inner = pair.into_inner();
-                let inner_expr = inner.next().unwrap();
-                Ast::Not(Box::new(get_ast_recursively(inner_expr)))
+                let identifier = inner.next().unwrap();
+                let body = inner.next().unwrap();
+                let operands = body
+                    .into_inner()
+                    .map(|operand| get_ast_recursively(operand, pratt))
+                    .collect::<FilsonResult<Vec<_>>>()?;
+                match identifier.as_str() {
+                    "and" => Ast::And(operands),
+                    "or" => Ast::Or(operands),
+                    "xor" | "one_of" => Ast::Xor(operands),
+                    _ => unreachable!(),
+                }
             }
             _ => unreachable!(),
-        }
+        };
+        Ok(ast)
     }
 
-    Ok(get_ast_recursively(pair))
+    PRATT.with(|pratt| get_ast_recursively(pair, pratt))
+}
+
+const COLLECTION_OR_STRING: &[ValueType] = &[ValueType::Collection, ValueType::String];
+
+/// Fails if the type of the subject is known and the actor can't be applied to it.
+fn parse_subject<'a>(
+    pair: Pair<'a, Rule>,
+    accepted: &[ValueType],
+    actor: &str,
+) -> FilsonResult<Subject<'a>> {
+    let span = pair.as_span();
+    let subject = Subject::try_from(pair)?;
+    subject.expect_type(span, accepted, actor)?;
+    Ok(subject)
+}
+
+/// `12d` is a duration rather than a decimal, which can't be compared with a number,
+/// so it fails next to the subjects known to be numbers, instead of when the filter is applied.
+fn parse_comparee<'a>(pair: Pair<'a, Rule>, subject: &Subject) -> FilsonResult<Operand<'a>> {
+    let is_number = matches!(subject.value_type(), Some(t) if t.overlaps(&ValueType::Number));
+    if is_number && pair.as_rule() == Rule::duration {
+        return Err(error_at(
+            pair.as_span(),
+            "numbers can't be compared with durations, decimals need the fractional part, e.g. `12.0d`",
+        ));
+    }
+    Operand::try_from(pair)
 }
 
 #[cfg(test)]
@@ -115,7 +313,17 @@ mod test_primitive_types_parsing {
     use crate::parser::ast_generation::{FilsonParser, Rule};
     use pest::{consumes_to, fails_with, parses_to};
 
-    const VALID_INTS: [&str; 4] = ["1", "+1", "-1", "1_2"];
+    const VALID_INTS: [&str; 9] = [
+        "1",
+        "+1",
+        "-1",
+        "1_2",
+        "0xFF",
+        "-0xdead_beef",
+        "+0o17",
+        "0b1010_0101",
+        "0x7fff_ffff_ffff_ffff",
+    ];
     const INVALID_INTS: [&str; 2] = ["_1", "1_"];
     const VALID_FLOATS: [&str; 11] = [
         "1.2", "+1.2", "-1.2", ".1", "1_2.34", "12.3_4", "1.0e1", "1.0E1", "1.0e+1", "1.0e-1",
@@ -208,6 +416,78 @@ mod test_primitive_types_parsing {
         };
     }
 
+    #[test]
+    fn parse_datetime() {
+        parses_to! {
+            parser: FilsonParser,
+            input: r#"t"2024-01-01T00:00:00+02:00""#,
+            rule: Rule::datetime,
+            tokens: [
+                datetime(0, 28, [datetime_chars(2, 27)])
+            ]
+        };
+        fails_with! {
+            parser: FilsonParser,
+            input: r#"t "2024-01-01T00:00:00Z""#,
+            rule: Rule::datetime,
+            positives: [Rule::datetime],
+            negatives: [],
+            pos: 0
+        };
+    }
+
+    #[test]
+    fn parse_duration() {
+        parses_to! {
+            parser: FilsonParser,
+            input: "1h30m",
+            rule: Rule::duration,
+            tokens: [
+                duration(0, 5)
+            ]
+        };
+        parses_to! {
+            parser: FilsonParser,
+            input: "-PT1H",
+            rule: Rule::duration,
+            tokens: [
+                duration(0, 5)
+            ]
+        };
+        fails_with! {
+            parser: FilsonParser,
+            input: "1",
+            rule: Rule::duration,
+            positives: [Rule::duration],
+            negatives: [],
+            pos: 0
+        };
+    }
+
+    #[test]
+    fn parse_decimal() {
+        for i in ["12.30d", "-1_000.5d", ".5d", "+0.0d"] {
+            parses_to! {
+                parser: FilsonParser,
+                input: i,
+                rule: Rule::decimal,
+                tokens: [
+                    decimal(0, i.len())
+                ]
+            };
+        }
+        for i in ["12d", "12.d", "1.0e1d"] {
+            fails_with! {
+                parser: FilsonParser,
+                input: i,
+                rule: Rule::decimal,
+                positives: [Rule::decimal],
+                negatives: [],
+                pos: 0
+            };
+        }
+    }
+
     #[test]
     fn parse_bool() {
         parses_to! {
@@ -315,7 +595,7 @@ mod test_complex_types_parsing {
             parser: FilsonParser,
             input: "[1, .1e_1, \"🦀\", true, null]",
             rule: Rule::array,
-            positives: [Rule::string, Rule::null, Rule::boolean],
+            positives: [Rule::string, Rule::datetime, Rule::duration, Rule::decimal, Rule::null, Rule::boolean],
             negatives: [],
             pos: 4
         };
@@ -352,7 +632,7 @@ mod test_complex_types_parsing {
             parser: FilsonParser,
             input: "{1, .1e_1, \"🦀\", true, null}",
             rule: Rule::set,
-            positives: [Rule::string, Rule::null, Rule::boolean],
+            positives: [Rule::string, Rule::datetime, Rule::duration, Rule::decimal, Rule::null, Rule::boolean],
             negatives: [],
             pos: 4
         };
@@ -425,6 +705,58 @@ mod test_compare_parsing {
                     ]
                 )
             ]
+        };
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"compare(len("/id") >= 3)"#,
+            rule: Rule::compare,
+            tokens: [
+                compare(0, 24, [
+                    function(8, 18, [
+                        function_identifier(8, 11),
+                        string(12, 17, [chars(13, 16)])
+                    ]),
+                    operation(19, 21),
+                    integer(22, 23)
+                    ]
+                )
+            ]
+        };
+    }
+
+    #[test]
+    fn test_reference() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"compare("/id" == @"/b")"#,
+            rule: Rule::compare,
+            tokens: [
+                compare(0, 23, [
+                    string(8, 13, [chars(9, 12)]),
+                    operation(14, 16),
+                    reference(17, 22, [
+                        string(18, 22, [chars(19, 21)])
+                    ])
+                    ]
+                )
+            ]
+        }
+    }
+
+    #[test]
+    fn test_parameter() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"compare("/id" == $tenant_1)"#,
+            rule: Rule::compare,
+            tokens: [
+                compare(0, 27, [
+                    string(8, 13, [chars(9, 12)]),
+                    operation(14, 16),
+                    parameter(17, 26, [parameter_name(18, 26)])
+                    ]
+                )
+            ]
         }
     }
 
@@ -434,7 +766,7 @@ mod test_compare_parsing {
            parser: FilsonParser,
             input: r#"compare("/id" == /)"#,
             rule: Rule::compare,
-            positives: [Rule::map, Rule::set, Rule::array, Rule::string, Rule::float, Rule::integer, Rule::null, Rule::boolean],
+            positives: [Rule::reference, Rule::parameter, Rule::now, Rule::map, Rule::set, Rule::array, Rule::string, Rule::datetime, Rule::duration, Rule::decimal, Rule::float, Rule::integer, Rule::null, Rule::boolean],
             negatives: [],
             pos: 17
         }
@@ -444,15 +776,76 @@ mod test_compare_parsing {
     fn test_invalid_left() {
         fails_with! {
            parser: FilsonParser,
-            input: r#"compare(1 == 1)"#,
+            input: r#"compare(true == 1)"#,
             rule: Rule::compare,
-            positives: [Rule::string],
+            positives: [Rule::function_identifier, Rule::arithmetic, Rule::path, Rule::string],
             negatives: [],
             pos: 8
+        };
+        // a lone number isn't an arithmetic expression
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"compare(1 == 1)"#,
+            rule: Rule::compare,
+            positives: [Rule::add_op, Rule::sub_op, Rule::mul_op, Rule::div_op, Rule::rem_op],
+            negatives: [],
+            pos: 10
         }
     }
 }
 
+#[cfg(test)]
+mod test_between_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
+
+    #[test]
+    fn test_valid() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"between("/id" 1 2)"#,
+            rule: Rule::between,
+            tokens: [
+                between(0, 18, [
+                    string(8, 13, [chars(9, 12)]),
+                    integer(14, 15),
+                    integer(16, 17)
+                    ]
+                )
+            ]
+        };
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"between("/id" [1, 2))"#,
+            rule: Rule::between,
+            tokens: [
+                between(0, 21, [
+                    string(8, 13, [chars(9, 12)]),
+                    interval(14, 20, [
+                        interval_open(14, 15),
+                        integer(15, 16),
+                        integer(18, 19),
+                        interval_close(19, 20)
+                    ])
+                    ]
+                )
+            ]
+        };
+    }
+
+    #[test]
+    fn test_invalid() {
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"between("/id" 1)"#,
+            rule: Rule::between,
+            positives: [Rule::reference, Rule::parameter, Rule::now, Rule::map, Rule::set, Rule::array, Rule::string, Rule::datetime, Rule::duration, Rule::decimal, Rule::float, Rule::integer, Rule::null, Rule::boolean],
+            negatives: [],
+            pos: 15
+        };
+    }
+}
+
 #[cfg(test)]
 mod test_intersects_parsing {
     use crate::parser::ast_generation::{FilsonParser, Rule};
@@ -480,7 +873,7 @@ mod test_intersects_parsing {
            parser: FilsonParser,
             input: r#"intersects(1 [])"#,
             rule: Rule::intersects,
-            positives: [Rule::string],
+            positives: [Rule::function_identifier, Rule::path_identifier, Rule::string],
             negatives: [],
             pos: 11
         }
@@ -492,7 +885,7 @@ mod test_intersects_parsing {
            parser: FilsonParser,
             input: r#"intersects("/id" 1)"#,
             rule: Rule::intersects,
-            positives: [Rule::map, Rule::set, Rule::array, Rule::string],
+            positives: [Rule::reference, Rule::parameter, Rule::map, Rule::set, Rule::array, Rule::string],
             negatives: [],
             pos: 17
         }
@@ -526,7 +919,7 @@ mod test_is_contained_parsing {
            parser: FilsonParser,
             input: r#"is_contained(1 [])"#,
             rule: Rule::is_contained,
-            positives: [Rule::string],
+            positives: [Rule::function_identifier, Rule::path_identifier, Rule::string],
             negatives: [],
             pos: 13
         }
@@ -538,7 +931,7 @@ mod test_is_contained_parsing {
            parser: FilsonParser,
             input: r#"is_contained("/id" 1)"#,
             rule: Rule::is_contained,
-            positives: [Rule::map, Rule::set, Rule::array],
+            positives: [Rule::reference, Rule::parameter, Rule::map, Rule::set, Rule::array],
             negatives: [],
             pos: 19
         }
@@ -571,13 +964,48 @@ mod test_exists_parsing {
            parser: FilsonParser,
             input: r#"exists(1)"#,
             rule: Rule::exists,
-            positives: [Rule::string],
+            positives: [Rule::path_identifier, Rule::string],
             negatives: [],
             pos: 7
         }
     }
 }
 
+#[cfg(test)]
+mod test_is_type_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
+
+    #[test]
+    fn test_valid() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"is_type("/id" int | null)"#,
+            rule: Rule::is_type,
+            tokens: [
+                is_type(0, 25, [
+                    string(8, 13, [chars(9, 12)]),
+                    value_type(14, 17),
+                    value_type(20, 24)
+                    ]
+                )
+            ]
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"is_type("/id" integer)"#,
+            rule: Rule::is_type,
+            positives: [Rule::value_type],
+            negatives: [],
+            pos: 14
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_is_superset_parsing {
     use crate::parser::ast_generation::{FilsonParser, Rule};
@@ -605,7 +1033,7 @@ mod test_is_superset_parsing {
            parser: FilsonParser,
             input: r#"is_superset(1 [])"#,
             rule: Rule::is_superset,
-            positives: [Rule::string],
+            positives: [Rule::function_identifier, Rule::path_identifier, Rule::string],
             negatives: [],
             pos: 12
         }
@@ -617,7 +1045,7 @@ mod test_is_superset_parsing {
            parser: FilsonParser,
             input: r#"is_superset("/id" 1)"#,
             rule: Rule::is_superset,
-            positives: [Rule::map, Rule::set, Rule::array, Rule::string],
+            positives: [Rule::reference, Rule::parameter, Rule::map, Rule::set, Rule::array, Rule::string],
             negatives: [],
             pos: 18
         }
@@ -651,7 +1079,7 @@ mod test_is_subset_parsing {
            parser: FilsonParser,
             input: r#"is_subset(1 [])"#,
             rule: Rule::is_subset,
-            positives: [Rule::string],
+            positives: [Rule::function_identifier, Rule::path_identifier, Rule::string],
             negatives: [],
             pos: 10
         }
@@ -663,7 +1091,7 @@ mod test_is_subset_parsing {
            parser: FilsonParser,
             input: r#"is_subset("/id" 1)"#,
             rule: Rule::is_subset,
-            positives: [Rule::map, Rule::set, Rule::array, Rule::string],
+            positives: [Rule::reference, Rule::parameter, Rule::map, Rule::set, Rule::array, Rule::string],
             negatives: [],
             pos: 16
         }
@@ -671,101 +1099,970 @@ mod test_is_subset_parsing {
 }
 
 #[cfg(test)]
-mod test_binary_ops_parsing {
+mod test_matches_parsing {
     use crate::parser::ast_generation::{FilsonParser, Rule};
     use pest::{consumes_to, fails_with, parses_to};
-    const BIN_OPS: [&str; 3] = ["and", "or", "xor"];
-
-    #[test]
-    fn test_valid_identifiers() {
-        for ident in BIN_OPS {
-            parses_to! {
-                parser: FilsonParser,
-                input: ident,
-                rule: Rule::binary_identifier,
-                tokens: [binary_identifier(0, ident.len())]
-            }
-        }
-    }
 
     #[test]
-    fn test_body_valid_no_nesting() {
+    fn test_valid() {
         parses_to! {
-        parser: FilsonParser,
-        input:r#"(exists("/id"), exists("/id"))"#,
-        rule: Rule::binary_body,
-        tokens: [
-            binary_body(0, 30, [
-                exists(1, 14, [string(8, 13, [chars(9, 12)])]),
-                exists(16, 29, [string(23, 28, [chars(24, 27)])])
-        ])
-        ]}
+           parser: FilsonParser,
+            input: r#"matches("/id" "^a+$" "i")"#,
+            rule: Rule::matches,
+            tokens: [
+                matches(0, 25, [
+                    string(8, 13, [chars(9, 12)]),
+                    string(14, 20, [chars(15, 19)]),
+                    string(21, 24, [chars(22, 23)])
+                    ]
+                )
+            ]
+        }
     }
 
     #[test]
-    fn test_body_valid_with_nesting() {
-        parses_to! {
-        parser: FilsonParser,
-        input:r#"(and(exists("/id"), exists("/id")), exists("/id"))"#,
-        rule: Rule::binary_body,
-        tokens: [
-            binary_body(0, 50, [
-                binary_operation(1, 34, [
-                    binary_identifier(1, 4),
-                    binary_body(4, 34, [
-                        exists(5, 18, [string(12, 17, [chars(13, 16)])]),
-                        exists(20, 33, [string(27, 32, [chars(28, 31)])]),
-                    ])
-                ]),
-                exists(36, 49, [string(43, 48, [chars(44, 47)])]),
-        ])
-        ]}
+    fn test_invalid_left() {
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"matches(1 "a")"#,
+            rule: Rule::matches,
+            positives: [Rule::function_identifier, Rule::path_identifier, Rule::string],
+            negatives: [],
+            pos: 8
+        }
     }
 
     #[test]
-    fn test_body_invalid_no_nesting() {
+    fn test_invalid_right() {
         fails_with! {
-            parser: FilsonParser,
-            input:r#"(karl, exists("/id"))"#,
-            rule: Rule::binary_body,
-            positives: [Rule::not, Rule::binary_identifier, Rule::compare,
-                        Rule::intersects, Rule::is_contained,
-                        Rule::exists, Rule::is_superset, Rule::is_subset],
+           parser: FilsonParser,
+            input: r#"matches("/id" [])"#,
+            rule: Rule::matches,
+            positives: [Rule::string],
             negatives: [],
-            pos: 1
+            pos: 14
         }
     }
 }
 
 #[cfg(test)]
-mod test_ast_generation {
-    use std::collections::{BTreeMap, BTreeSet};
+mod test_string_predicate_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
 
-    use super::*;
-    use crate::{types::Op, DataNode};
+    const IDENTIFIERS: [&str; 6] = [
+        "starts_with",
+        "istarts_with",
+        "ends_with",
+        "iends_with",
+        "contains_str",
+        "icontains_str",
+    ];
 
     #[test]
-    fn parse_not() {
-        assert_eq!(
-            get_ast(r#"!compare("/id" == null)"#).unwrap(),
-            Ast::Not(Box::new(Ast::Compare {
-                lhs: "/id",
-                op: Op::Eq,
-                rhs: DataNode::Null
-            }))
+    fn test_valid_identifiers() {
+        for ident in IDENTIFIERS {
+            parses_to! {
+                parser: FilsonParser,
+                input: ident,
+                rule: Rule::string_predicate_identifier,
+                tokens: [string_predicate_identifier(0, ident.len())]
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"starts_with("/id" "a")"#,
+            rule: Rule::string_predicate,
+            tokens: [
+                string_predicate(0, 22, [
+                    string_predicate_identifier(0, 11),
+                    string(12, 17, [chars(13, 16)]),
+                    string(18, 21, [chars(19, 20)])
+                    ]
+                )
+            ]
+        }
+    }
+
+    #[test]
+    fn test_invalid_right() {
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"ends_with("/id" 1)"#,
+            rule: Rule::string_predicate,
+            positives: [Rule::string],
+            negatives: [],
+            pos: 16
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_quantifier_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
+
+    #[test]
+    fn test_valid_identifiers() {
+        for ident in ["any", "all", "none"] {
+            parses_to! {
+                parser: FilsonParser,
+                input: ident,
+                rule: Rule::quantifier_identifier,
+                tokens: [quantifier_identifier(0, ident.len())]
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"any("/a", exists("/b"))"#,
+            rule: Rule::quantifier,
+            tokens: [
+                quantifier(0, 23, [
+                    quantifier_identifier(0, 3),
+                    string(4, 8, [chars(5, 7)]),
+                    condition(10, 22, [exists(10, 22, [string(17, 21, [chars(18, 20)])])])
+                    ]
+                )
+            ]
+        }
+    }
+
+    #[test]
+    fn test_valid_count() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"count("/a", exists("/b")) > 1"#,
+            rule: Rule::count,
+            tokens: [
+                count(0, 29, [
+                    string(6, 10, [chars(7, 9)]),
+                    condition(12, 24, [exists(12, 24, [string(19, 23, [chars(20, 22)])])]),
+                    operation(26, 27),
+                    integer(28, 29)
+                    ]
+                )
+            ]
+        }
+    }
+
+    #[test]
+    fn test_invalid_path() {
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"all(1, exists("/b"))"#,
+            rule: Rule::quantifier,
+            positives: [Rule::path_identifier, Rule::string],
+            negatives: [],
+            pos: 4
+        }
+    }
+
+    #[test]
+    fn test_count_without_operation() {
+        fails_with! {
+           parser: FilsonParser,
+            input: r#"count("/a", exists("/b"))"#,
+            rule: Rule::count,
+            positives: [Rule::operation],
+            negatives: [],
+            pos: 25
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_path_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
+
+    #[test]
+    fn test_valid() {
+        parses_to! {
+           parser: FilsonParser,
+            input: r#"a.b_1[0]."c d"[*].*"#,
+            rule: Rule::path,
+            tokens: [
+                path(0, 19, [
+                    path_identifier(0, 1),
+                    path_identifier(2, 5),
+                    path_index(6, 7),
+                    string(9, 14, [chars(10, 13)]),
+                    path_wildcard(15, 16),
+                    path_wildcard(18, 19)
+                    ]
+                )
+            ]
+        }
+    }
+
+    #[test]
+    fn test_keywords() {
+        parses_to! {
+           parser: FilsonParser,
+            input: "nullable.true",
+            rule: Rule::path,
+            tokens: [path(0, 13, [path_identifier(0, 8), path_identifier(9, 13)])]
+        };
+        fails_with! {
+           parser: FilsonParser,
+            input: "null.a",
+            rule: Rule::path,
+            positives: [Rule::path],
+            negatives: [],
+            pos: 0
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        fails_with! {
+           parser: FilsonParser,
+            input: "1a",
+            rule: Rule::path,
+            positives: [Rule::path_identifier],
+            negatives: [],
+            pos: 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_logical_ops_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
+    const LOGICAL_OPS: [&str; 4] = ["and", "or", "xor", "one_of"];
+
+    #[test]
+    fn test_valid_identifiers() {
+        for ident in LOGICAL_OPS {
+            parses_to! {
+                parser: FilsonParser,
+                input: ident,
+                rule: Rule::logical_identifier,
+                tokens: [logical_identifier(0, ident.len())]
+            }
+        }
+    }
+
+    #[test]
+    fn test_body_valid_no_nesting() {
+        parses_to! {
+        parser: FilsonParser,
+        input:r#"(exists("/id"), exists("/id"))"#,
+        rule: Rule::logical_body,
+        tokens: [
+            logical_body(0, 30, [
+                condition(1, 14, [exists(1, 14, [string(8, 13, [chars(9, 12)])])]),
+                condition(16, 29, [exists(16, 29, [string(23, 28, [chars(24, 27)])])])
+        ])
+        ]}
+    }
+
+    #[test]
+    fn test_body_valid_with_nesting() {
+        parses_to! {
+        parser: FilsonParser,
+        input:r#"(and(exists("/id"), exists("/id")), exists("/id"))"#,
+        rule: Rule::logical_body,
+        tokens: [
+            logical_body(0, 50, [
+                condition(1, 34, [
+                    logical_operation(1, 34, [
+                        logical_identifier(1, 4),
+                        logical_body(4, 34, [
+                            condition(5, 18, [exists(5, 18, [string(12, 17, [chars(13, 16)])])]),
+                            condition(20, 33, [exists(20, 33, [string(27, 32, [chars(28, 31)])])]),
+                        ])
+                    ])
+                ]),
+                condition(36, 49, [exists(36, 49, [string(43, 48, [chars(44, 47)])])]),
+        ])
+        ]}
+    }
+
+    #[test]
+    fn test_body_invalid_no_nesting() {
+        fails_with! {
+            parser: FilsonParser,
+            input:r#"(karl, exists("/id"))"#,
+            rule: Rule::logical_body,
+            positives: [Rule::condition],
+            negatives: [],
+            pos: 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_infix_parsing {
+    use crate::parser::ast_generation::{FilsonParser, Rule};
+    use pest::{consumes_to, fails_with, parses_to};
+
+    #[test]
+    fn test_valid() {
+        parses_to! {
+        parser: FilsonParser,
+        input:r#"!exists("/a") && (exists("/b") || exists("/c"))"#,
+        rule: Rule::condition,
+        tokens: [
+            condition(0, 47, [
+                not(0, 1),
+                exists(1, 13, [string(8, 12, [chars(9, 11)])]),
+                and_op(14, 16),
+                condition(18, 46, [
+                    exists(18, 30, [string(25, 29, [chars(26, 28)])]),
+                    or_op(31, 33),
+                    exists(34, 46, [string(41, 45, [chars(42, 44)])]),
+                ]),
+            ])
+        ]}
+    }
+
+    #[test]
+    fn test_invalid_operand() {
+        fails_with! {
+            parser: FilsonParser,
+            input:r#"exists("/a") && 1"#,
+            rule: Rule::expression,
+            positives: [Rule::not, Rule::logical_identifier, Rule::compare, Rule::between,
+                        Rule::intersects, Rule::is_contained,
+                        Rule::exists, Rule::is_type, Rule::is_superset, Rule::is_subset, Rule::matches,
+                        Rule::string_predicate_identifier, Rule::quantifier_identifier, Rule::count],
+            negatives: [],
+            pos: 16
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_ast_generation {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use chrono::{DateTime, Duration};
+    use pest::error::InputLocation;
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::{
+        ast::{Operand, Subject},
+        types::{ArithmeticOp, Function, Op},
+        DataNode, Path, PathSegment,
+    };
+
+    #[test]
+    fn parse_not() {
+        assert_eq!(
+            get_ast(r#"!compare("/id" == null)"#).unwrap(),
+            Ast::Not(Box::new(Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Eq,
+                rhs: DataNode::Null.into()
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_compare() {
+        assert_eq!(
+            get_ast(r#"compare("/id" == null)"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Eq,
+                rhs: DataNode::Null.into()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_functions() {
+        assert_eq!(
+            get_ast(r#"compare(len("/id") >= 3)"#).unwrap(),
+            Ast::Compare {
+                lhs: Subject::Function {
+                    function: Function::Len,
+                    arg: Box::new("/id".into())
+                },
+                op: Op::Gte,
+                rhs: 3.into()
+            }
+        );
+        assert_eq!(
+            get_ast(r#"between(byte_len("/id") 1 2)"#).unwrap(),
+            Ast::Between {
+                lhs: Subject::Function {
+                    function: Function::ByteLen,
+                    arg: Box::new("/id".into())
+                },
+                lower: 1.into(),
+                upper: 2.into(),
+                lower_inclusive: true,
+                upper_inclusive: true,
+            }
+        );
+        assert_eq!(
+            get_ast(r#"compare(abs("/a" - "/b") < 1)"#).unwrap(),
+            Ast::Compare {
+                lhs: Subject::Function {
+                    function: Function::Abs,
+                    arg: Box::new(Subject::Arithmetic {
+                        op: ArithmeticOp::Sub,
+                        lhs: Box::new("/a".into()),
+                        rhs: Box::new("/b".into())
+                    })
+                },
+                op: Op::Lt,
+                rhs: 1.into()
+            }
+        );
+        assert_eq!(
+            get_ast(r#"matches(upper(trim("/id")) "^A")"#).unwrap(),
+            Ast::Matches {
+                lhs: Subject::Function {
+                    function: Function::Upper,
+                    arg: Box::new(Subject::Function {
+                        function: Function::Trim,
+                        arg: Box::new("/id".into())
+                    })
+                },
+                rhs: Pattern::new("^A", "").unwrap()
+            }
+        );
+        assert!(get_ast(r#"compare(len(1) >= 3)"#).is_err());
+        assert!(get_ast(r#"compare(size("/id") >= 3)"#).is_err());
+        assert!(get_ast(r#"exists(len("/id"))"#).is_err());
+    }
+
+    #[test]
+    fn functions_are_type_checked() {
+        let error_message = |inp| {
+            let err = get_ast(inp).unwrap_err();
+            let crate::FilsonError::ParseError(err) = err else {
+                panic!("expected parse error, got {err:?}")
+            };
+            (err.variant.message().into_owned(), err.location)
+        };
+        assert_eq!(
+            error_message(r#"intersects(len("/id") [1])"#),
+            (
+                "intersects expects collection or string, got int".to_owned(),
+                InputLocation::Span((11, 21))
+            )
+        );
+        assert_eq!(
+            error_message(r#"compare(lower(len("/id")) == "a")"#),
+            (
+                "lower expects string, got int".to_owned(),
+                InputLocation::Span((14, 24))
+            )
+        );
+        assert_eq!(
+            error_message(r#"compare(keys(values("/id")) == {})"#),
+            (
+                "keys expects map, got array".to_owned(),
+                InputLocation::Span((13, 26))
+            )
+        );
+        assert_eq!(
+            error_message(r#"compare(round("/a" * 2) + to_string("/b") > 1)"#),
+            (
+                "arithmetic expects number, got string".to_owned(),
+                InputLocation::Span((26, 41))
+            )
+        );
+        assert_eq!(
+            error_message(r#"starts_with(to_int("/a") "1")"#),
+            (
+                "starts_with expects string, got int".to_owned(),
+                InputLocation::Span((12, 24))
+            )
+        );
+        let duration_error =
+            "numbers can't be compared with durations, decimals need the fractional part, e.g. `12.0d`";
+        assert_eq!(
+            error_message(r#"compare(abs("/a") == 100d)"#),
+            (duration_error.to_owned(), InputLocation::Span((21, 25)))
+        );
+        assert_eq!(
+            error_message(r#"between("/a" * 2 0.5d 12d)"#),
+            (duration_error.to_owned(), InputLocation::Span((22, 25)))
+        );
+        // types by the paths aren't known before the filter is applied
+        assert!(get_ast(r#"compare(abs(round("/a")) + len("/b") > 1)"#).is_ok());
+        assert!(get_ast(r#"intersects(keys("/a") {"team", "env"})"#).is_ok());
+    }
+
+    #[test]
+    fn parse_arithmetic() {
+        fn arithmetic<'a>(op: ArithmeticOp, lhs: Subject<'a>, rhs: Subject<'a>) -> Subject<'a> {
+            Subject::Arithmetic {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            }
+        }
+        fn subject(inp: &str) -> Subject<'_> {
+            match get_ast(inp).unwrap() {
+                Ast::Compare { lhs, .. } => lhs,
+                _ => unreachable!(),
+            }
+        }
+
+        assert_eq!(
+            subject(r#"compare("/a" + "/b" * 2 > 1)"#),
+            arithmetic(
+                ArithmeticOp::Add,
+                "/a".into(),
+                arithmetic(ArithmeticOp::Mul, "/b".into(), Subject::Literal(2.into()))
+            )
+        );
+        assert_eq!(
+            subject(r#"compare("/a" - 1 - 2.5 > 1)"#),
+            arithmetic(
+                ArithmeticOp::Sub,
+                arithmetic(ArithmeticOp::Sub, "/a".into(), Subject::Literal(1.into())),
+                Subject::Literal(2.5.into())
+            )
+        );
+        assert_eq!(
+            subject(r#"compare(-("/a" % len("/b")) / -1.0d > 1)"#),
+            arithmetic(
+                ArithmeticOp::Div,
+                Subject::Neg(Box::new(arithmetic(
+                    ArithmeticOp::Rem,
+                    "/a".into(),
+                    Subject::Function {
+                        function: Function::Len,
+                        arg: Box::new("/b".into())
+                    }
+                ))),
+                Subject::Neg(Box::new(Subject::Literal(Decimal::ONE.into())))
+            )
+        );
+        assert_eq!(
+            subject(r#"compare(-"/a" > 1)"#),
+            Subject::Neg(Box::new("/a".into()))
         );
+        assert_eq!(subject(r#"compare(("/a") > 1)"#), "/a".into());
+        assert!(get_ast(r#"compare("/a" + > 1)"#).is_err());
+        assert!(get_ast(r#"compare("/a" + "b" 1)"#).is_err());
+        assert!(get_ast(r#"compare("/a" + true > 1)"#).is_err());
+        assert!(get_ast(r#"intersects("/a" + 1 [1])"#).is_err());
     }
 
     #[test]
-    fn parse_compare() {
+    fn parse_references() {
         assert_eq!(
-            get_ast(r#"compare("/id" == null)"#).unwrap(),
+            get_ast(r#"compare("/id" >= @"/other")"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Gte,
+                rhs: Operand::Path("/other".into())
+            }
+        );
+        assert_eq!(
+            get_ast(r#"is_contained("/id" @"/ids")"#).unwrap(),
+            Ast::IsContained {
+                lhs: "/id".into(),
+                rhs: Operand::Path("/ids".into())
+            }
+        );
+        assert_eq!(
+            get_ast(r#"between("/id" [@"/min", @"/max"))"#).unwrap(),
+            Ast::Between {
+                lhs: "/id".into(),
+                lower: Operand::Path("/min".into()),
+                upper: Operand::Path("/max".into()),
+                lower_inclusive: true,
+                upper_inclusive: false,
+            }
+        );
+        assert!(get_ast(r#"compare("/id" == [@"/other"])"#).is_err());
+        assert!(get_ast(r#"matches("/id" @"/other")"#).is_err());
+        assert!(get_ast(r#"compare(@"/id" == 1)"#).is_err());
+    }
+
+    #[test]
+    fn parse_quantifiers() {
+        assert_eq!(
+            get_ast(r#"any("/items", compare("/price" > 100) && exists("/qty"))"#).unwrap(),
+            Ast::Any {
+                path: "/items".into(),
+                condition: Box::new(Ast::And(vec![
+                    Ast::Compare {
+                        lhs: "/price".into(),
+                        op: Op::Gt,
+                        rhs: 100.into()
+                    },
+                    Ast::Exists {
+                        path: "/qty".into()
+                    }
+                ]))
+            }
+        );
+        assert_eq!(
+            get_ast(r#"none("/tags", all("", compare("" == 1)))"#).unwrap(),
+            Ast::None {
+                path: "/tags".into(),
+                condition: Box::new(Ast::All {
+                    path: "".into(),
+                    condition: Box::new(Ast::Compare {
+                        lhs: "".into(),
+                        op: Op::Eq,
+                        rhs: 1.into()
+                    })
+                })
+            }
+        );
+        assert_eq!(
+            get_ast(r#"count("/items", exists("/sku")) >= 2 || !exists("/items")"#).unwrap(),
+            Ast::Or(vec![
+                Ast::Count {
+                    path: "/items".into(),
+                    condition: Box::new(Ast::Exists {
+                        path: "/sku".into()
+                    }),
+                    op: Op::Gte,
+                    rhs: 2.into()
+                },
+                Ast::Not(Box::new(Ast::Exists {
+                    path: "/items".into()
+                }))
+            ])
+        );
+        assert!(get_ast(r#"any("/items" exists("/sku"))"#).is_err());
+        assert!(get_ast(r#"count("/items", exists("/sku")) >= 2.0"#).is_err());
+        assert!(get_ast(r#"compare(count("/items", exists("/sku")) >= 2)"#).is_err());
+    }
+
+    #[test]
+    fn into_owned_keeps_the_ast() {
+        let cond = r#"compare(lower(a."b c") == "x") && !exists("/d") || any(items[*], starts_with(name "\u0041")) ^^ between(len("/e") @f $g) || matches("/h" "^i$" "i")"#;
+        let ast = get_ast(cond).unwrap();
+        let owned = ast.clone().into_owned();
+        assert_eq!(owned, ast);
+    }
+
+    #[test]
+    fn parse_params() {
+        let ast = get_ast(
+            r#"compare("/a" == $a) && any("/items", between("/b" ($b, $c])) || is_contained("/d" $d) || compare("/e" > $a)"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ast,
+            Ast::Or(vec![
+                Ast::And(vec![
+                    Ast::Compare {
+                        lhs: "/a".into(),
+                        op: Op::Eq,
+                        rhs: Operand::Param("a".into())
+                    },
+                    Ast::Any {
+                        path: "/items".into(),
+                        condition: Box::new(Ast::Between {
+                            lhs: "/b".into(),
+                            lower: Operand::Param("b".into()),
+                            upper: Operand::Param("c".into()),
+                            lower_inclusive: false,
+                            upper_inclusive: true,
+                        })
+                    }
+                ]),
+                Ast::IsContained {
+                    lhs: "/d".into(),
+                    rhs: Operand::Param("d".into())
+                },
+                Ast::Compare {
+                    lhs: "/e".into(),
+                    op: Op::Gt,
+                    rhs: Operand::Param("a".into())
+                }
+            ])
+        );
+        assert_eq!(
+            ast.params().into_iter().collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+        assert!(get_ast(r#"compare("/a" == $)"#).is_err());
+        assert!(get_ast(r#"compare("/a" == @$a)"#).is_err());
+        assert!(get_ast(r#"matches("/a" $a)"#).is_err());
+    }
+
+    #[test]
+    fn parse_native_paths() {
+        let key = |key: &'static str| PathSegment::Key(key.into());
+        assert_eq!(
+            get_ast(r#"compare(items[0]."unit price" * qty > @limits.max)"#).unwrap(),
+            Ast::Compare {
+                lhs: Subject::Arithmetic {
+                    op: ArithmeticOp::Mul,
+                    lhs: Box::new(Subject::Path(
+                        Path::new(vec![key("items"), PathSegment::Index(0), key("unit price")])
+                            .into()
+                    )),
+                    rhs: Box::new(Subject::Path(Path::new(vec![key("qty")]).into())),
+                },
+                op: Op::Gt,
+                rhs: Operand::Path(Path::new(vec![key("limits"), key("max")]).into()),
+            }
+        );
+        assert_eq!(
+            get_ast(r#"any(items[*].tags, exists(id)) && exists("/id")"#).unwrap(),
+            Ast::And(vec![
+                Ast::Any {
+                    path: Path::new(vec![key("items"), PathSegment::Wildcard, key("tags")]).into(),
+                    condition: Box::new(Ast::Exists {
+                        path: Path::new(vec![key("id")]).into()
+                    })
+                },
+                Ast::Exists { path: "/id".into() }
+            ])
+        );
+        // strings are never split, so the keys may contain dots
+        assert_eq!(
+            get_ast(r#"compare(a."b.c" == 1)"#).unwrap(),
+            Ast::Compare {
+                lhs: Subject::Path(Path::new(vec![key("a"), key("b.c")]).into()),
+                op: Op::Eq,
+                rhs: 1.into()
+            }
+        );
+        assert!(get_ast(r#"compare(a..b == 1)"#).is_err());
+        assert!(get_ast(r#"compare(a[-1] == 1)"#).is_err());
+        assert!(get_ast(r#"compare(a[99999999999999999999] == 1)"#).is_err());
+        assert!(get_ast(r#"compare(null == 1)"#).is_err());
+        assert!(get_ast(r#"compare(1 == a)"#).is_err());
+    }
+
+    #[test]
+    fn parse_escapes() {
+        assert_eq!(
+            get_ast(r#"compare("/caf\u00e9/\"id\"" == "a\nb\uD83E\uDD80")"#).unwrap(),
             Ast::Compare {
-                lhs: "/id",
+                lhs: "/café/\"id\"".into(),
                 op: Op::Eq,
-                rhs: DataNode::Null
+                rhs: "a\nb🦀".into()
+            }
+        );
+        assert_eq!(
+            get_ast(r#"is_contained("/id" ["\t", {"\\"}])"#).unwrap(),
+            Ast::IsContained {
+                lhs: "/id".into(),
+                rhs: vec!["\t".into(), BTreeSet::from_iter(vec!["\\".into()]).into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_invalid_escapes() {
+        let err = get_ast(r#"compare("/id" == "ab\uD83E")"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((20, 26)));
+
+        let err = get_ast(r#"exists("\uDD80")"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((8, 14)));
+    }
+
+    #[test]
+    fn parse_datetime_literals() {
+        assert_eq!(
+            get_ast(r#"compare("/id" > t"2024-01-01T02:00:00.5+02:00")"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Gt,
+                rhs: DateTime::parse_from_rfc3339("2024-01-01T00:00:00.5Z")
+                    .unwrap()
+                    .into()
+            }
+        );
+        let err = get_ast(r#"compare("/id" > t"2024-13-01T00:00:00Z")"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((18, 38)));
+        assert!(get_ast(r#"compare("/id" > t"2024-01-01")"#).is_err());
+    }
+
+    #[test]
+    fn parse_radix_integers() {
+        assert_eq!(
+            get_ast(r#"is_contained("/id" [0xFF, -0o17, 0b1_0])"#).unwrap(),
+            Ast::IsContained {
+                lhs: "/id".into(),
+                rhs: vec![255.into(), (-15).into(), 2.into()].into()
+            }
+        );
+        assert!(get_ast(r#"compare("/id" == 0x_FF)"#).is_err());
+        assert!(get_ast(r#"compare("/id" == 0xFF_)"#).is_err());
+        assert!(get_ast(r#"compare("/id" == 0b12)"#).is_err());
+        assert!(get_ast(r#"compare("/id" == 0X1)"#).is_err());
+    }
+
+    #[test]
+    fn parse_wide_integers() {
+        assert_eq!(
+            get_ast(r#"is_contained("/id" [-1, 0xFFFF_FFFF_FFFF_FFFF, -0x8000_0000_0000_0001])"#)
+                .unwrap(),
+            Ast::IsContained {
+                lhs: "/id".into(),
+                rhs: vec![
+                    DataNode::I64(-1),
+                    DataNode::U64(u64::MAX),
+                    DataNode::I128(i64::MIN as i128 - 1)
+                ]
+                .into()
+            }
+        );
+        let Ast::Compare { rhs, .. } =
+            get_ast(r#"compare("/id" == 340282366920938463463374607431768211455)"#).unwrap()
+        else {
+            panic!("expected compare")
+        };
+        assert!(matches!(rhs, Operand::Literal(DataNode::U128(u128::MAX))));
+    }
+
+    #[test]
+    fn parse_overflowing_integers() {
+        assert!(matches!(
+            get_ast(r#"compare("/id" == 0x1_0000_0000_0000_0000_0000_0000_0000_0000)"#)
+                .unwrap_err(),
+            crate::FilsonError::NumberParseError {
+                source: lexical::Error::Overflow(_),
+                span: (17, 60)
+            }
+        ));
+        assert!(matches!(
+            get_ast(r#"compare("/id" == [1, -0x8000_0000_0000_0000_0000_0000_0000_0001])"#)
+                .unwrap_err(),
+            crate::FilsonError::NumberParseError {
+                source: lexical::Error::Underflow(_),
+                span: (21, 63)
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_decimals() {
+        assert_eq!(
+            get_ast(r#"is_contained("/id" [12.30d, -1_000.5d, 12d])"#).unwrap(),
+            Ast::IsContained {
+                lhs: "/id".into(),
+                rhs: vec![
+                    DataNode::Decimal(Decimal::new(1230, 2)),
+                    DataNode::Decimal(Decimal::new(-10005, 1)),
+                    Duration::days(12).into()
+                ]
+                .into()
+            }
+        );
+        let err = get_ast(r#"compare("/id" == 0.00000000000000000000000000001d)"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((17, 49)));
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(
+            get_ast(r#"compare("/id" > 1h30m)"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Gt,
+                rhs: Duration::minutes(90).into()
+            }
+        );
+        assert_eq!(
+            get_ast(r#"is_contained("/id" [PT1H, -7d])"#).unwrap(),
+            Ast::IsContained {
+                lhs: "/id".into(),
+                rhs: vec![Duration::hours(1).into(), Duration::days(-7).into()].into()
             }
         );
+        let err = get_ast(r#"compare("/id" > P1Y)"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((16, 19)));
+    }
+
+    #[test]
+    fn parse_relative_time() {
+        assert_eq!(
+            get_ast(r#"compare("/id" > now())"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Gt,
+                rhs: Operand::Now(Duration::zero())
+            }
+        );
+        assert_eq!(
+            get_ast(r#"compare("/id" > now() - 7d)"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Gt,
+                rhs: Operand::Now(Duration::days(-7))
+            }
+        );
+        assert_eq!(
+            get_ast(r#"compare("/id" > now()+PT1H)"#).unwrap(),
+            Ast::Compare {
+                lhs: "/id".into(),
+                op: Op::Gt,
+                rhs: Operand::Now(Duration::hours(1))
+            }
+        );
+        assert!(get_ast(r#"compare("/id" > now() - 7)"#).is_err());
+        assert!(get_ast(r#"is_contained("/id" [now()])"#).is_err());
+    }
+
+    #[test]
+    fn parse_between() {
+        assert_eq!(
+            get_ast(r#"between("/id" 1 "a")"#).unwrap(),
+            Ast::Between {
+                lhs: "/id".into(),
+                lower: 1.into(),
+                upper: "a".into(),
+                lower_inclusive: true,
+                upper_inclusive: true,
+            }
+        );
+        assert_eq!(
+            get_ast(r#"between("/id" (now() - 7d, now()])"#).unwrap(),
+            Ast::Between {
+                lhs: "/id".into(),
+                lower: Operand::Now(Duration::days(-7)),
+                upper: Operand::Now(Duration::zero()),
+                lower_inclusive: false,
+                upper_inclusive: true,
+            }
+        );
+        assert_eq!(
+            get_ast(r#"between("/id" [1, 2] [3])"#).unwrap(),
+            Ast::Between {
+                lhs: "/id".into(),
+                lower: vec![1.into(), 2.into()].into(),
+                upper: vec![3.into()].into(),
+                lower_inclusive: true,
+                upper_inclusive: true,
+            }
+        );
+        assert!(get_ast(r#"between("/id" [1, 2, 3])"#).is_err());
+        assert!(get_ast(r#"between("/id" 1, 2)"#).is_err());
     }
 
     #[test]
@@ -773,28 +2070,28 @@ mod test_ast_generation {
         assert_eq!(
             get_ast(r#"intersects("/id" [1])"#).unwrap(),
             Ast::Intersects {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: vec![1.into()].into()
             }
         );
         assert_eq!(
             get_ast(r#"intersects("/id" {1})"#).unwrap(),
             Ast::Intersects {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeSet::from_iter(vec![1.into()]).into()
             }
         );
         assert_eq!(
             get_ast(r#"intersects("/id" <1:1>)"#).unwrap(),
             Ast::Intersects {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeMap::from_iter(vec![(1.into(), 1.into())]).into()
             }
         );
         assert_eq!(
             get_ast(r#"intersects("/id" "karl")"#).unwrap(),
             Ast::Intersects {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: "karl".into()
             }
         );
@@ -805,21 +2102,21 @@ mod test_ast_generation {
         assert_eq!(
             get_ast("is_contained(\"/id\" [1])").unwrap(),
             Ast::IsContained {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: vec![1.into()].into(),
             }
         );
         assert_eq!(
             get_ast("is_contained(\"/id\" {1})").unwrap(),
             Ast::IsContained {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeSet::from_iter(vec![1.into()]).into(),
             }
         );
         assert_eq!(
             get_ast("is_contained(\"/id\" <1:1>)").unwrap(),
             Ast::IsContained {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeMap::from_iter(vec![(1.into(), 1.into())]).into(),
             }
         );
@@ -829,37 +2126,57 @@ mod test_ast_generation {
     fn parse_exists() {
         assert_eq!(
             get_ast(r#"exists("/id")"#).unwrap(),
-            Ast::Exists { path: "/id" }
+            Ast::Exists { path: "/id".into() }
         )
     }
 
+    #[test]
+    fn parse_is_type() {
+        assert_eq!(
+            get_ast(r#"is_type("/id" number)"#).unwrap(),
+            Ast::IsType {
+                lhs: "/id".into(),
+                types: vec![ValueType::Number]
+            }
+        );
+        assert_eq!(
+            get_ast(r#"is_type("/id" map|set | null)"#).unwrap(),
+            Ast::IsType {
+                lhs: "/id".into(),
+                types: vec![ValueType::Map, ValueType::Set, ValueType::Null]
+            }
+        );
+        assert!(get_ast(r#"is_type("/id")"#).is_err());
+        assert!(get_ast(r#"is_type("/id" "int")"#).is_err());
+    }
+
     #[test]
     fn parse_is_superset() {
         assert_eq!(
             get_ast(r#"is_superset("/id" [1])"#).unwrap(),
             Ast::IsSuperset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: vec![1.into()].into(),
             }
         );
         assert_eq!(
             get_ast(r#"is_superset("/id" {1})"#).unwrap(),
             Ast::IsSuperset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeSet::from_iter(vec![1.into()]).into(),
             }
         );
         assert_eq!(
             get_ast(r#"is_superset("/id" <1:1>)"#).unwrap(),
             Ast::IsSuperset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeMap::from_iter(vec![(1.into(), 1.into())]).into(),
             }
         );
         assert_eq!(
             get_ast(r#"is_superset("/id" "karl")"#).unwrap(),
             Ast::IsSuperset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: "karl".into()
             }
         );
@@ -870,93 +2187,256 @@ mod test_ast_generation {
         assert_eq!(
             get_ast(r#"is_subset("/id" [1])"#).unwrap(),
             Ast::IsSubset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: vec![1.into()].into(),
             }
         );
         assert_eq!(
             get_ast(r#"is_subset("/id" {1})"#).unwrap(),
             Ast::IsSubset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeSet::from_iter(vec![1.into()]).into(),
             }
         );
         assert_eq!(
             get_ast(r#"is_subset("/id" <1:1>)"#).unwrap(),
             Ast::IsSubset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: BTreeMap::from_iter(vec![(1.into(), 1.into())]).into(),
             }
         );
         assert_eq!(
             get_ast(r#"is_subset("/id" "karl")"#).unwrap(),
             Ast::IsSubset {
-                lhs: "/id",
+                lhs: "/id".into(),
                 rhs: "karl".into()
             }
         );
     }
 
     #[test]
-    fn parse_binary_and_condition_no_nesting() {
+    fn parse_matches() {
+        assert_eq!(
+            get_ast(r#"matches("/id" "^a\\d+$")"#).unwrap(),
+            Ast::Matches {
+                lhs: "/id".into(),
+                rhs: Pattern::new(r"^a\d+$", "").unwrap()
+            }
+        );
+        assert_eq!(
+            get_ast(r#"matches("/id" "^a" "im")"#).unwrap(),
+            Ast::Matches {
+                lhs: "/id".into(),
+                rhs: Pattern::new("^a", "im").unwrap()
+            }
+        );
+        // the order and repetitions of the flags don't matter
+        assert_eq!(
+            get_ast(r#"matches("/id" "^a" "mii")"#).unwrap(),
+            get_ast(r#"matches("/id" "^a" "im")"#).unwrap()
+        );
+        assert_ne!(
+            get_ast(r#"matches("/id" "^a" "m")"#).unwrap(),
+            get_ast(r#"matches("/id" "^a" "im")"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_matches() {
+        let err = get_ast(r#"matches("/id" "a(b")"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((14, 19)));
+
+        let err = get_ast(r#"matches("/id" "a" "iq")"#).unwrap_err();
+        let crate::FilsonError::ParseError(err) = err else {
+            panic!("expected parse error, got {err:?}")
+        };
+        assert_eq!(err.location, InputLocation::Span((18, 22)));
+
+        // exceeds the size limit
+        assert!(get_ast(r#"matches("/id" "(\\w{100}){100}")"#).is_err());
+    }
+
+    #[test]
+    fn parse_string_predicates() {
+        assert_eq!(
+            get_ast(r#"starts_with("/id" "Ab")"#).unwrap(),
+            Ast::StartsWith {
+                lhs: "/id".into(),
+                rhs: "Ab".into(),
+                case_insensitive: false
+            }
+        );
+        assert_eq!(
+            get_ast(r#"iends_with("/id" "Ab")"#).unwrap(),
+            Ast::EndsWith {
+                lhs: "/id".into(),
+                rhs: "ab".into(),
+                case_insensitive: true
+            }
+        );
+        assert_eq!(
+            get_ast(r#"icontains_str("/id" "ÀB")"#).unwrap(),
+            Ast::ContainsStr {
+                lhs: "/id".into(),
+                rhs: "àb".into(),
+                case_insensitive: true
+            }
+        );
+        assert!(get_ast(r#"i starts_with("/id" "Ab")"#).is_err());
+    }
+
+    #[test]
+    fn parse_logical_and_condition_no_nesting() {
         assert_eq!(
             get_ast(r#"and(exists("/id"), compare("/id" == null))"#).unwrap(),
-            Ast::And(
-                Box::new(Ast::Exists { path: "/id" }),
-                Box::new(Ast::Compare {
-                    lhs: "/id",
+            Ast::And(vec![
+                Ast::Exists { path: "/id".into() },
+                Ast::Compare {
+                    lhs: "/id".into(),
                     op: Op::Eq,
-                    rhs: DataNode::Null
-                })
-            )
+                    rhs: DataNode::Null.into()
+                }
+            ])
         )
     }
 
     #[test]
-    fn parse_binary_and_or_xor_not_conditions_multiple_nesting() {
+    fn parse_logical_variadic_conditions() {
+        let operands = vec![
+            Ast::Exists { path: "/a".into() },
+            Ast::Exists { path: "/b".into() },
+            Ast::Exists { path: "/c".into() },
+        ];
+        assert_eq!(
+            get_ast(r#"and(exists("/a"), exists("/b"), exists("/c"))"#).unwrap(),
+            Ast::And(operands.clone())
+        );
+        assert_eq!(
+            get_ast(r#"or(exists("/a"), exists("/b"), exists("/c"))"#).unwrap(),
+            Ast::Or(operands.clone())
+        );
+        assert_eq!(
+            get_ast(r#"xor(exists("/a"), exists("/b"), exists("/c"))"#).unwrap(),
+            Ast::Xor(operands.clone())
+        );
+        assert_eq!(
+            get_ast(r#"one_of(exists("/a"), exists("/b"), exists("/c"))"#).unwrap(),
+            Ast::Xor(operands)
+        );
+        assert!(get_ast(r#"and(exists("/a"))"#).is_err());
+        assert!(get_ast(r#"and(exists("/a"), )"#).is_err());
+    }
+
+    #[test]
+    fn parse_logical_and_or_xor_not_conditions_multiple_nesting() {
         assert_eq!(
             get_ast(r#"xor(and(!exists("/id"), compare("/id" == null)), !or(exists("/id"), compare("/id" == null)))"#)
                 .unwrap(),
-            Ast::Xor(
-                Box::new(Ast::And(
-                    Box::new(
-                        Ast::Not(
-                            Box::new(
-                                Ast::Exists{
-                                    path: "/id"
-                                }
-                            )
-                        )
-                    ),
-                    Box::new(Ast::Compare {
-                        lhs:"/id",
+            Ast::Xor(vec![
+                Ast::And(vec![
+                    Ast::Not(Box::new(Ast::Exists { path: "/id".into() })),
+                    Ast::Compare {
+                        lhs: "/id".into(),
                         op: Op::Eq,
-                        rhs: DataNode::Null
-                        }
-                    )
-                )
-            ),
-                Box::new(
-                    Ast::Not(
-                        Box::new(
-                            Ast::Or(
-                                Box::new(
-                                    Ast::Exists{
-                                        path: "/id"
-                                    }
-                                ),
-                                Box::new(
-                                    Ast::Compare{
-                                        lhs: "/id",
-                                        op:Op::Eq,
-                                        rhs: DataNode::Null
-                                    }
-                                )
-                            )
-                        )
-                    )
-                )
-            )
+                        rhs: DataNode::Null.into()
+                    }
+                ]),
+                Ast::Not(Box::new(Ast::Or(vec![
+                    Ast::Exists { path: "/id".into() },
+                    Ast::Compare {
+                        lhs: "/id".into(),
+                        op: Op::Eq,
+                        rhs: DataNode::Null.into()
+                    }
+                ])))
+            ])
         )
     }
+
+    #[test]
+    fn parse_infix_precedence() {
+        let a = || Ast::Exists { path: "/a".into() };
+        let b = || Ast::Exists { path: "/b".into() };
+        let c = || Ast::Exists { path: "/c".into() };
+        assert_eq!(
+            get_ast(r#"exists("/a") && exists("/b") || !exists("/c")"#).unwrap(),
+            Ast::Or(vec![Ast::And(vec![a(), b()]), Ast::Not(Box::new(c()))])
+        );
+        assert_eq!(
+            get_ast(r#"exists("/a") || exists("/b") ^^ exists("/c") && exists("/a")"#).unwrap(),
+            Ast::Or(vec![a(), Ast::Xor(vec![b(), Ast::And(vec![c(), a()])])])
+        );
+        assert_eq!(
+            get_ast(r#"!exists("/a") && !(exists("/b") || exists("/c"))"#).unwrap(),
+            Ast::And(vec![
+                Ast::Not(Box::new(a())),
+                Ast::Not(Box::new(Ast::Or(vec![b(), c()])))
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_infix_chains() {
+        let a = || Ast::Exists { path: "/a".into() };
+        let b = || Ast::Exists { path: "/b".into() };
+        let c = || Ast::Exists { path: "/c".into() };
+        assert_eq!(
+            get_ast(r#"exists("/a") && exists("/b") && exists("/c")"#).unwrap(),
+            Ast::And(vec![a(), b(), c()])
+        );
+        assert_eq!(
+            get_ast(r#"exists("/a") || exists("/b") || exists("/c")"#).unwrap(),
+            Ast::Or(vec![a(), b(), c()])
+        );
+        assert_eq!(
+            get_ast(r#"exists("/a") ^^ exists("/b") ^^ exists("/c")"#).unwrap(),
+            Ast::Xor(vec![a(), b(), c()])
+        );
+        assert_eq!(
+            get_ast(r#"exists("/a") ^^ (exists("/b") ^^ exists("/c"))"#).unwrap(),
+            Ast::Xor(vec![a(), Ast::Xor(vec![b(), c()])])
+        );
+        assert_eq!(
+            get_ast(r#"(exists("/a") ^^ exists("/b")) ^^ exists("/c")"#).unwrap(),
+            Ast::Xor(vec![Ast::Xor(vec![a(), b()]), c()])
+        );
+        assert_eq!(
+            get_ast(r#"xor(exists("/a"), exists("/b")) ^^ exists("/c")"#).unwrap(),
+            Ast::Xor(vec![Ast::Xor(vec![a(), b()]), c()])
+        );
+        assert_eq!(
+            get_ast(r#"(exists("/a") && exists("/b")) && exists("/c")"#).unwrap(),
+            Ast::And(vec![Ast::And(vec![a(), b()]), c()])
+        );
+        assert_eq!(
+            get_ast(r#"exists("/a") && (exists("/b") && exists("/c"))"#).unwrap(),
+            Ast::And(vec![a(), Ast::And(vec![b(), c()])])
+        );
+    }
+
+    #[test]
+    fn parse_infix_mixed_with_prefix() {
+        assert_eq!(
+            get_ast(r#"or(exists("/a") && exists("/b"), (exists("/c")))"#).unwrap(),
+            Ast::Or(vec![
+                Ast::And(vec![
+                    Ast::Exists { path: "/a".into() },
+                    Ast::Exists { path: "/b".into() }
+                ]),
+                Ast::Exists { path: "/c".into() }
+            ])
+        );
+        assert!(get_ast(r#"exists("/a") &&"#).is_err());
+        assert!(get_ast(r#"(exists("/a")"#).is_err());
+        assert!(get_ast(r#"exists("/a") & exists("/b")"#).is_err());
+    }
+
+    #[test]
+    fn parse_trailing_input() {
+        assert!(get_ast(r#"exists("/id") exists("/id")"#).is_err());
+        assert!(get_ast(r#"!exists("/id") 1"#).is_err());
+    }
 }
@@ -2,4 +2,7 @@ mod ast_generation;
 mod parse_utils;
 
 pub(crate) use ast_generation::{get_ast, Rule};
-pub(crate) use parse_utils::{parse_float, parse_int};
+pub(crate) use parse_utils::{
+    error_at, parse_datetime, parse_decimal_literal, parse_duration, parse_duration_literal,
+    parse_float_literal, parse_int_literal, parse_string, unescape,
+};
@@ -1,12 +1,28 @@
+use chrono::{DateTime, Duration, FixedOffset};
 use core::num::NonZeroU8;
-use lexical::parse_with_options;
+use lexical::{parse_with_options, Error as LexicalError, FromLexicalWithOptions};
+use pest::{
+    error::{Error as PestError, ErrorVariant},
+    iterators::Pair,
+    Span,
+};
+use rust_decimal::Decimal;
+use std::{borrow::Cow, ops::Range};
 
-use crate::{error::FilsonResult, FilsonError};
+use crate::{error::FilsonResult, parser::Rule, DataNode, FilsonError};
 
-const INT_FORMAT: u128 = lexical::NumberFormatBuilder::new()
-    .digit_separator(NonZeroU8::new(b'_'))
-    .internal_digit_separator(true)
-    .build();
+const fn int_format(radix: u8) -> u128 {
+    lexical::NumberFormatBuilder::new()
+        .mantissa_radix(radix)
+        .digit_separator(NonZeroU8::new(b'_'))
+        .internal_digit_separator(true)
+        .build()
+}
+
+const INT_FORMAT: u128 = int_format(10);
+const HEX_INT_FORMAT: u128 = int_format(16);
+const OCT_INT_FORMAT: u128 = int_format(8);
+const BIN_INT_FORMAT: u128 = int_format(2);
 
 const FLOAT_FORMAT: u128 = lexical::NumberFormatBuilder::new()
     .digit_separator(NonZeroU8::new(b'_'))
@@ -16,16 +32,306 @@ const FLOAT_FORMAT: u128 = lexical::NumberFormatBuilder::new()
     .case_sensitive_exponent(false)
     .build();
 
-pub(crate) fn parse_int(inp_str: &str) -> FilsonResult<i64> {
+/// Parses decimal integer or the one prefixed by `0x`, `0o` or `0b`, sign goes before the prefix.
+pub(crate) fn parse_int<T>(inp_str: &str) -> Result<T, LexicalError>
+where
+    T: FromLexicalWithOptions<Options = lexical::ParseIntegerOptions>,
+{
     let options = lexical::ParseIntegerOptions::new();
-    parse_with_options::<i64, _, INT_FORMAT>(inp_str.as_bytes(), &options)
-        .map_err(FilsonError::from)
+    let (sign, unsigned) = match inp_str.as_bytes().first() {
+        Some(b'-' | b'+') => inp_str.split_at(1),
+        _ => ("", inp_str),
+    };
+    // the sign has to be parsed along with the digits, since i64::MIN has no positive counterpart
+    let strip_prefix = || format!("{sign}{}", &unsigned[2..]);
+    match unsigned.get(..2) {
+        Some("0x") => {
+            parse_with_options::<T, _, HEX_INT_FORMAT>(strip_prefix().as_bytes(), &options)
+        }
+        Some("0o") => {
+            parse_with_options::<T, _, OCT_INT_FORMAT>(strip_prefix().as_bytes(), &options)
+        }
+        Some("0b") => {
+            parse_with_options::<T, _, BIN_INT_FORMAT>(strip_prefix().as_bytes(), &options)
+        }
+        _ => parse_with_options::<T, _, INT_FORMAT>(inp_str.as_bytes(), &options),
+    }
 }
 
-pub(crate) fn parse_float(inp_str: &str) -> FilsonResult<f64> {
+pub(crate) fn parse_float(inp_str: &str) -> Result<f64, LexicalError> {
     let options = lexical::ParseFloatOptions::new();
     parse_with_options::<f64, _, FLOAT_FORMAT>(inp_str.as_bytes(), &options)
-        .map_err(FilsonError::from)
+}
+
+/// Parses contents of the `integer` rule into the narrowest integer type it fits into, errors point at the literal.
+pub(crate) fn parse_int_literal(pair: Pair<'_, Rule>) -> FilsonResult<DataNode<'static>> {
+    let node = match parse_int::<i128>(pair.as_str()) {
+        Ok(i) => i64::try_from(i)
+            .map(DataNode::from)
+            .or_else(|_| u64::try_from(i).map(DataNode::from))
+            .unwrap_or(DataNode::I128(i)),
+        Err(LexicalError::Overflow(_)) => parse_int::<u128>(pair.as_str())
+            .map(DataNode::from)
+            .map_err(|err| number_parse_error(pair.as_span(), err))?,
+        Err(err) => return Err(number_parse_error(pair.as_span(), err)),
+    };
+    Ok(node)
+}
+
+/// Parses contents of the `float` rule, errors point at the literal.
+pub(crate) fn parse_float_literal(pair: Pair<'_, Rule>) -> FilsonResult<f64> {
+    parse_float(pair.as_str()).map_err(|err| number_parse_error(pair.as_span(), err))
+}
+
+/// Parses contents of the `decimal` rule, which has to be represented exactly.
+pub(crate) fn parse_decimal_literal(pair: Pair<'_, Rule>) -> FilsonResult<Decimal> {
+    let digits = pair.as_str().trim_end_matches('d').replace('_', "");
+    Decimal::from_str_exact(&digits)
+        .map_err(|err| error_at(pair.as_span(), format!("invalid decimal: {err}")))
+}
+
+fn number_parse_error(span: Span<'_>, source: LexicalError) -> FilsonError {
+    FilsonError::NumberParseError {
+        source,
+        span: (span.start(), span.end()),
+    }
+}
+
+/// Describes an escape sequence that can't be decoded and where it is located within the input.
+#[derive(Debug, PartialEq)]
+pub(crate) struct InvalidEscape {
+    pub(crate) range: Range<usize>,
+    pub(crate) reason: &'static str,
+}
+
+impl InvalidEscape {
+    fn new(range: Range<usize>, reason: &'static str) -> Self {
+        Self { range, reason }
+    }
+}
+
+/// Decodes escape sequences in the contents of a string literal.
+///
+/// Borrows the input when there is nothing to decode.
+pub(crate) fn unescape(inp: &str) -> Result<Cow<'_, str>, InvalidEscape> {
+    if !inp.contains('\\') {
+        return Ok(Cow::Borrowed(inp));
+    }
+    let mut res = String::with_capacity(inp.len());
+    let mut idx = 0;
+    while let Some(offset) = inp[idx..].find('\\') {
+        let start = idx + offset;
+        res.push_str(&inp[idx..start]);
+        let decoded = match inp[start + 1..].chars().next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let (ch, len) = decode_unicode_escape(inp, start)?;
+                res.push(ch);
+                idx = start + len;
+                continue;
+            }
+            Some(ch) => {
+                return Err(InvalidEscape::new(
+                    start..start + 1 + ch.len_utf8(),
+                    "unknown escape sequence",
+                ))
+            }
+            None => {
+                return Err(InvalidEscape::new(
+                    start..start + 1,
+                    "incomplete escape sequence",
+                ))
+            }
+        };
+        res.push(decoded);
+        idx = start + 2;
+    }
+    res.push_str(&inp[idx..]);
+    Ok(Cow::Owned(res))
+}
+
+/// Decodes `\uXXXX` escape starting at `start`, combining surrogate pairs.
+///
+/// Returns the decoded char and the length of the consumed input.
+fn decode_unicode_escape(inp: &str, start: usize) -> Result<(char, usize), InvalidEscape> {
+    let high = read_code_unit(inp, start)?;
+    match high {
+        0xD800..=0xDBFF => {
+            let low = if inp[start + 6..].starts_with("\\u") {
+                read_code_unit(inp, start + 6)?
+            } else {
+                return Err(InvalidEscape::new(
+                    start..start + 6,
+                    "high surrogate is not followed by a low surrogate",
+                ));
+            };
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(InvalidEscape::new(
+                    start..start + 12,
+                    "high surrogate is not followed by a low surrogate",
+                ));
+            }
+            let code_point = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            // can't fail, any valid surrogate pair decodes to a valid char
+            Ok((char::from_u32(code_point).unwrap(), 12))
+        }
+        0xDC00..=0xDFFF => Err(InvalidEscape::new(
+            start..start + 6,
+            "low surrogate is not preceded by a high surrogate",
+        )),
+        // can't fail, surrogates are handled above
+        _ => Ok((char::from_u32(high).unwrap(), 6)),
+    }
+}
+
+fn read_code_unit(inp: &str, start: usize) -> Result<u32, InvalidEscape> {
+    inp.get(start + 2..start + 6)
+        .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| {
+            InvalidEscape::new(
+                start..inp.len().min(start + 6),
+                "expected 4 hex digits in unicode escape sequence",
+            )
+        })
+}
+
+/// Builds [FilsonError::ParseError] pointing at the `span` of the input.
+pub(crate) fn error_at(span: Span<'_>, message: impl Into<String>) -> FilsonError {
+    let err = PestError::new_from_span(
+        ErrorVariant::CustomError {
+            message: message.into(),
+        },
+        span,
+    );
+    FilsonError::ParseError(Box::new(err))
+}
+
+/// Decodes contents of the `string` rule.
+pub(crate) fn parse_string(pair: Pair<'_, Rule>) -> FilsonResult<Cow<'_, str>> {
+    let chars = pair.into_inner().next().unwrap();
+    let span = chars.as_span();
+    unescape(chars.as_str()).map_err(|err| {
+        let err_span = span.get(err.range).unwrap_or(span);
+        error_at(err_span, err.reason)
+    })
+}
+
+/// Parses contents of the `datetime` rule, which is expected to be in RFC 3339 format.
+pub(crate) fn parse_datetime(pair: Pair<'_, Rule>) -> FilsonResult<DateTime<FixedOffset>> {
+    let chars = pair.into_inner().next().unwrap();
+    DateTime::parse_from_rfc3339(chars.as_str())
+        .map_err(|err| error_at(chars.as_span(), format!("invalid RFC 3339 datetime: {err}")))
+}
+
+/// Parses contents of the `duration` rule.
+pub(crate) fn parse_duration_literal(pair: Pair<'_, Rule>) -> FilsonResult<Duration> {
+    parse_duration(pair.as_str()).map_err(|reason| error_at(pair.as_span(), reason))
+}
+
+/// Parses either a short duration (e.g. `1h30m`) or an ISO 8601 one (e.g. `PT1H30M`), both with an optional sign.
+///
+/// Years and months are rejected, since their length varies.
+pub(crate) fn parse_duration(inp: &str) -> Result<Duration, &'static str> {
+    let (negative, unsigned) = match inp.as_bytes().first() {
+        Some(b'-') => (true, &inp[1..]),
+        Some(b'+') => (false, &inp[1..]),
+        _ => (false, inp),
+    };
+    let duration = match unsigned.strip_prefix('P') {
+        Some(iso) => parse_iso_duration(iso)?,
+        None => parse_short_duration(unsigned)?,
+    };
+    Ok(if negative { -duration } else { duration })
+}
+
+fn parse_short_duration(inp: &str) -> Result<Duration, &'static str> {
+    // otherwise an empty string or a bare sign would be a zero duration
+    if inp.is_empty() {
+        return Err("duration has no components");
+    }
+    sum_duration_components(inp, |unit, rest| match unit {
+        // `ms` has to be checked before `m`
+        'm' if rest.starts_with('s') => Some((Duration::try_milliseconds as fn(i64) -> _, 2)),
+        'w' => Some((Duration::try_weeks, 1)),
+        'd' => Some((Duration::try_days, 1)),
+        'h' => Some((Duration::try_hours, 1)),
+        'm' => Some((Duration::try_minutes, 1)),
+        's' => Some((Duration::try_seconds, 1)),
+        _ => None,
+    })
+}
+
+fn parse_iso_duration(inp: &str) -> Result<Duration, &'static str> {
+    let (date, time) = match inp.split_once('T') {
+        Some((_, "")) => return Err("expected time components after 'T'"),
+        Some((date, time)) => (date, time),
+        None => (inp, ""),
+    };
+    if date.is_empty() && time.is_empty() {
+        return Err("duration has no components");
+    }
+    let date = sum_duration_components(date, |unit, _| match unit {
+        'W' => Some((Duration::try_weeks as fn(i64) -> _, 1)),
+        'D' => Some((Duration::try_days, 1)),
+        _ => None,
+    })?;
+    let time = sum_duration_components(time, |unit, _| match unit {
+        'H' => Some((Duration::try_hours as fn(i64) -> _, 1)),
+        'M' => Some((Duration::try_minutes, 1)),
+        'S' => Some((Duration::try_seconds, 1)),
+        _ => None,
+    })?;
+    date.checked_add(&time).ok_or("duration is out of range")
+}
+
+type DurationUnit = (fn(i64) -> Option<Duration>, usize);
+
+/// Sums up `<number><unit>` components, `unit` maps unit's first char and the input following it into a constructor
+/// and the length of the unit.
+fn sum_duration_components(
+    mut inp: &str,
+    unit: impl Fn(char, &str) -> Option<DurationUnit>,
+) -> Result<Duration, &'static str> {
+    let mut total = Duration::zero();
+    while !inp.is_empty() {
+        let digits_end = inp
+            .find(|ch: char| !ch.is_ascii_digit())
+            .ok_or("expected a unit after the number")?;
+        if digits_end == 0 {
+            return Err(match inp.chars().next() {
+                Some('Y' | 'M') => "years and months aren't supported, since their length varies",
+                _ => "expected a number",
+            });
+        }
+        let rest = &inp[digits_end..];
+        // can't fail, the loop condition and `find` above guarantee there is at least one char
+        let ch = rest.chars().next().unwrap();
+        let (constructor, unit_len) = match unit(ch, &rest[1..]) {
+            Some(unit) => unit,
+            None if matches!(ch, 'Y' | 'M') => {
+                return Err("years and months aren't supported, since their length varies")
+            }
+            None => return Err("unknown duration unit"),
+        };
+        let component = inp[..digits_end]
+            .parse()
+            .ok()
+            .and_then(constructor)
+            .ok_or("duration is out of range")?;
+        total = total
+            .checked_add(&component)
+            .ok_or("duration is out of range")?;
+        inp = &rest[unit_len..];
+    }
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -34,12 +340,46 @@ mod tests {
 
     #[test]
     fn test_parse_int() {
-        assert_eq!(parse_int("1").unwrap(), 1);
-        assert_eq!(parse_int("+1").unwrap(), 1);
-        assert_eq!(parse_int("-1").unwrap(), -1);
-        assert_eq!(parse_int("1_2").unwrap(), 12);
-        assert!(parse_int("_1").is_err());
-        assert!(parse_int("1_").is_err());
+        assert_eq!(parse_int::<i64>("1").unwrap(), 1);
+        assert_eq!(parse_int::<i64>("+1").unwrap(), 1);
+        assert_eq!(parse_int::<i64>("-1").unwrap(), -1);
+        assert_eq!(parse_int::<i64>("1_2").unwrap(), 12);
+        assert!(parse_int::<i64>("_1").is_err());
+        assert!(parse_int::<i64>("1_").is_err());
+        assert_eq!(parse_int::<i64>("0xFF").unwrap(), 255);
+        assert_eq!(parse_int::<i64>("0xdead_beef").unwrap(), 0xdead_beef);
+        assert_eq!(parse_int::<i64>("-0x10").unwrap(), -16);
+        assert_eq!(parse_int::<i64>("+0o17").unwrap(), 15);
+        assert_eq!(parse_int::<i64>("0b1010_0101").unwrap(), 0b1010_0101);
+        assert_eq!(
+            parse_int::<i64>("-0x8000_0000_0000_0000").unwrap(),
+            i64::MIN
+        );
+        assert_eq!(parse_int::<i64>("0x7FFF_FFFF_FFFF_FFFF").unwrap(), i64::MAX);
+        assert!(matches!(
+            parse_int::<i64>("0x8000_0000_0000_0000"),
+            Err(LexicalError::Overflow(_))
+        ));
+        assert!(matches!(
+            parse_int::<i64>("9223372036854775808"),
+            Err(LexicalError::Overflow(_))
+        ));
+        assert!(matches!(
+            parse_int::<i64>("-9223372036854775809"),
+            Err(LexicalError::Underflow(_))
+        ));
+        assert!(parse_int::<i64>("0b102").is_err());
+        assert!(parse_int::<i64>("0o8").is_err());
+        assert!(parse_int::<i64>("0x").is_err());
+        assert_eq!(parse_int::<u64>("0xFFFF_FFFF_FFFF_FFFF").unwrap(), u64::MAX);
+        assert_eq!(
+            parse_int::<i128>("-170141183460469231731687303715884105728").unwrap(),
+            i128::MIN
+        );
+        assert_eq!(
+            parse_int::<u128>("0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF").unwrap(),
+            u128::MAX
+        );
     }
 
     #[test]
@@ -65,4 +405,114 @@ mod tests {
         assert_eq!(parse_float("1.0e-1").unwrap(), 0.1);
         assert_eq!(parse_float("1.0e1_0").unwrap(), 10000000000.0);
     }
+
+    #[test]
+    fn test_unescape() {
+        assert!(matches!(unescape("plain"), Ok(Cow::Borrowed("plain"))));
+        assert_eq!(unescape(r#"a\nb"#).unwrap(), "a\nb");
+        assert_eq!(
+            unescape(r#"\"\\\/\b\f\n\r\t"#).unwrap(),
+            "\"\\/\u{8}\u{c}\n\r\t"
+        );
+        assert_eq!(unescape(r#"caf\u00e9"#).unwrap(), "café");
+        assert_eq!(unescape(r#"\uD83E\uDD80!"#).unwrap(), "🦀!");
+        assert_eq!(unescape(r#"🦀\t🦀"#).unwrap(), "🦀\t🦀");
+    }
+
+    #[test]
+    fn test_unescape_invalid() {
+        assert_eq!(
+            unescape(r#"ab\uD83E"#),
+            Err(InvalidEscape::new(
+                2..8,
+                "high surrogate is not followed by a low surrogate"
+            ))
+        );
+        assert_eq!(
+            unescape(r#"\uD83E\u0041"#),
+            Err(InvalidEscape::new(
+                0..12,
+                "high surrogate is not followed by a low surrogate"
+            ))
+        );
+        assert_eq!(
+            unescape(r#"a\uDD80"#),
+            Err(InvalidEscape::new(
+                1..7,
+                "low surrogate is not preceded by a high surrogate"
+            ))
+        );
+        assert_eq!(
+            unescape(r#"\u12"#),
+            Err(InvalidEscape::new(
+                0..4,
+                "expected 4 hex digits in unicode escape sequence"
+            ))
+        );
+        assert_eq!(
+            unescape(r#"\q"#),
+            Err(InvalidEscape::new(0..2, "unknown escape sequence"))
+        );
+        assert_eq!(
+            unescape("\\"),
+            Err(InvalidEscape::new(0..1, "incomplete escape sequence"))
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("7d"), Ok(Duration::days(7)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::minutes(15)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::milliseconds(500)));
+        assert_eq!(
+            parse_duration("1h30m15s"),
+            Ok(Duration::seconds(60 * 60 + 30 * 60 + 15))
+        );
+        assert_eq!(parse_duration("2w"), Ok(Duration::weeks(2)));
+        assert_eq!(parse_duration("-1h"), Ok(Duration::hours(-1)));
+        assert_eq!(parse_duration("+1h"), Ok(Duration::hours(1)));
+        assert_eq!(parse_duration("PT1H"), Ok(Duration::hours(1)));
+        assert_eq!(
+            parse_duration("P1DT2H3M4S"),
+            Ok(Duration::seconds(24 * 60 * 60 + 2 * 60 * 60 + 3 * 60 + 4))
+        );
+        assert_eq!(parse_duration("P1W"), Ok(Duration::weeks(1)));
+        assert_eq!(parse_duration("-PT1M"), Ok(Duration::minutes(-1)));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert_eq!(parse_duration("7"), Err("expected a unit after the number"));
+        assert_eq!(parse_duration("7x"), Err("unknown duration unit"));
+        assert_eq!(parse_duration("d"), Err("expected a number"));
+        assert_eq!(parse_duration("P"), Err("duration has no components"));
+        for inp in ["", "-", "+", "-P"] {
+            assert_eq!(
+                parse_duration(inp),
+                Err("duration has no components"),
+                "{inp}"
+            );
+        }
+        assert_eq!(
+            parse_duration("P1DT"),
+            Err("expected time components after 'T'")
+        );
+        assert_eq!(
+            parse_duration("P1Y"),
+            Err("years and months aren't supported, since their length varies")
+        );
+        assert_eq!(
+            parse_duration("P1M"),
+            Err("years and months aren't supported, since their length varies")
+        );
+        assert_eq!(parse_duration("PT1D"), Err("unknown duration unit"));
+        assert_eq!(
+            parse_duration("99999999999999999999d"),
+            Err("duration is out of range")
+        );
+        assert_eq!(
+            parse_duration("9999999999999w"),
+            Err("duration is out of range")
+        );
+    }
 }
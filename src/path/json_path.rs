@@ -0,0 +1,677 @@
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+    ops::Range,
+    str::FromStr,
+};
+
+use pest::{error::InputLocation, iterators::Pair, Parser};
+
+use crate::{
+    ast::{Ast, Context, DataPath, Operand, Subject},
+    parser::unescape,
+    path::{Navigable, Path, PathSegment},
+    types::Op,
+    DataNode, FilsonError, FilsonResult, FilterOptions, Params, SystemClock,
+};
+
+#[derive(pest_derive::Parser)]
+#[grammar = "json_path.pest"]
+struct JsonPathParser;
+
+/// Compiled [JSONPath](https://www.rfc-editor.org/rfc/rfc9535) expression, e.g. `$.store.book[0:2].title`.
+///
+/// Supports member names, wildcards, indices, slices, unions, recursive descent (`..`) and filter selectors (`[?...]`).
+/// Filter selectors may compare singular queries relative to the current value (`@`) with each other or with literals,
+/// test if a query refers to any value, and combine those with `&&`, `||`, `!` and parentheses.
+/// Function extensions (e.g. `length(@)`) and queries from the root (`$`) aren't supported in filter selectors.
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use serde_json::json;
+/// use filson::{JsonPath, Navigable};
+///
+/// let data = json!({"store": {"book": [{"price": 8}, {"price": 12}, {"price": 30}]}});
+/// let path: JsonPath = "$..book[-2:].price".parse().unwrap();
+/// assert_eq!(path.select(&data), vec![&json!(12), &json!(30)]);
+/// // paths that start with `$` are JSONPath
+/// assert_eq!(data.select("$.store.book[0,2].price").unwrap(), vec![&json!(8), &json!(30)]);
+/// assert_eq!(data.select("$.store.book[?@.price > 10].price").unwrap(), vec![&json!(12), &json!(30)]);
+/// # }
+/// ```
+///
+/// Paths are equal if they are written the same way.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    /// Selectors are applied to the value and all of its descendants, not only to the value itself.
+    descendant: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    /// Children of the value the expression holds for.
+    Filter(FilterExpr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterExpr {
+    Or(Vec<FilterExpr>),
+    And(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+    /// The query refers to at least one value.
+    Exists(JsonPath),
+    /// Lowered onto the `compare` actor, which is evaluated against the child.
+    /// It doesn't hold where the actor would fail, e.g. when there is no value to compare or the types mismatch.
+    Compare(Ast<'static>),
+}
+
+/// Syntax error in a JSONPath, `range` is the byte range of the invalid part of it.
+#[derive(Debug)]
+pub(crate) struct JsonPathError {
+    pub(crate) range: Range<usize>,
+    pub(crate) message: String,
+}
+
+impl JsonPathError {
+    fn at(pair: &Pair<'_, Rule>, message: String) -> Self {
+        let span = pair.as_span();
+        Self {
+            range: span.start()..span.end(),
+            message,
+        }
+    }
+}
+
+type JsonPathResult<T> = Result<T, JsonPathError>;
+
+impl JsonPath {
+    pub fn new(inp: &str) -> FilsonResult<Self> {
+        Self::parse(inp).map_err(|err| {
+            FilsonError::PathParseError(format!(
+                "{} at {}..{}",
+                err.message, err.range.start, err.range.end
+            ))
+        })
+    }
+
+    pub(crate) fn parse(inp: &str) -> JsonPathResult<Self> {
+        let path = JsonPathParser::parse(Rule::json_path, inp)
+            .map_err(|err| {
+                let range = match err.location {
+                    InputLocation::Pos(pos) => pos..pos,
+                    InputLocation::Span((start, end)) => start..end,
+                };
+                let message = err.variant.message().into_owned();
+                JsonPathError { range, message }
+            })?
+            .next()
+            .unwrap();
+        let segments = path
+            .into_inner()
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .map(Segment::try_from)
+            .collect::<JsonPathResult<_>>()?;
+        Ok(Self {
+            source: inp.to_owned(),
+            segments,
+        })
+    }
+
+    /// The path as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Values the path refers to, in the order they appear in the data.
+    pub fn select<'v, N: Navigable>(&self, root: &'v N) -> Vec<&'v N> {
+        let mut values = vec![root];
+        for segment in &self.segments {
+            let mut inputs = Vec::new();
+            for value in values {
+                if segment.descendant {
+                    push_descendants(value, &mut inputs);
+                } else {
+                    inputs.push(value);
+                }
+            }
+            values = Vec::new();
+            for input in inputs {
+                for selector in &segment.selectors {
+                    selector.select(input, &mut values);
+                }
+            }
+        }
+        values
+    }
+}
+
+impl PartialEq for JsonPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for JsonPath {}
+
+impl Hash for JsonPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = FilsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+/// Pushes the value itself, followed by all of its descendants in document order.
+fn push_descendants<'v, N: Navigable>(value: &'v N, out: &mut Vec<&'v N>) {
+    out.push(value);
+    for child in value.children() {
+        push_descendants(child, out);
+    }
+}
+
+impl Selector {
+    fn select<'v, N: Navigable>(&self, value: &'v N, out: &mut Vec<&'v N>) {
+        match self {
+            Selector::Name(name) => out.extend(value.member(name)),
+            Selector::Wildcard => out.extend(value.children()),
+            Selector::Index(index) => {
+                if let Some(elements) = value.elements() {
+                    let len = elements.len() as i64;
+                    let index = if *index < 0 { len + index } else { *index };
+                    if (0..len).contains(&index) {
+                        out.push(&elements[index as usize]);
+                    }
+                }
+            }
+            Selector::Slice { start, end, step } => {
+                if let Some(elements) = value.elements() {
+                    let indices = slice_indices(elements.len() as i64, *start, *end, *step);
+                    out.extend(indices.map(|i| &elements[i as usize]));
+                }
+            }
+            Selector::Filter(expr) => out.extend(
+                value
+                    .children()
+                    .into_iter()
+                    .filter(|child| expr.test(*child)),
+            ),
+        }
+    }
+}
+
+impl FilterExpr {
+    fn test<N: Navigable>(&self, value: &N) -> bool {
+        match self {
+            FilterExpr::Or(exprs) => exprs.iter().any(|expr| expr.test(value)),
+            FilterExpr::And(exprs) => exprs.iter().all(|expr| expr.test(value)),
+            FilterExpr::Not(expr) => !expr.test(value),
+            FilterExpr::Exists(query) => !query.select(value).is_empty(),
+            FilterExpr::Compare(ast) => {
+                let params = Params::new();
+                // numbers of different types are compared by their value, as the RFC requires
+                let options = FilterOptions::default().numeric_coercion(true);
+                ast.evaluate(value, &Context::new(&SystemClock, options, &params))
+                    .unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// Indices selected by the slice in an array of `len` elements, as defined in the section 2.3.4.2.2 of the RFC.
+fn slice_indices(
+    len: i64,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+) -> impl Iterator<Item = i64> {
+    let step = step.unwrap_or(1);
+    let normalize = |i: i64| if i >= 0 { i } else { len + i };
+    let (mut i, bound) = if step >= 0 {
+        let lower = start.map_or(0, normalize).clamp(0, len);
+        let upper = end.map_or(len, normalize).clamp(0, len);
+        (lower, upper)
+    } else {
+        let upper = start.map_or(len - 1, normalize).clamp(-1, len - 1);
+        let lower = end.map_or(-1, normalize).clamp(-1, len - 1);
+        (upper, lower)
+    };
+    std::iter::from_fn(move || {
+        let in_bounds = if step > 0 { i < bound } else { i > bound };
+        if step == 0 || !in_bounds {
+            return None;
+        }
+        let current = i;
+        // saturates past the bound, so the iteration stops
+        i = i.saturating_add(step);
+        Some(current)
+    })
+}
+
+impl TryFrom<Pair<'_, Rule>> for Segment {
+    type Error = JsonPathError;
+
+    fn try_from(pair: Pair<'_, Rule>) -> JsonPathResult<Self> {
+        let descendant = pair.as_rule() == Rule::descendant_segment;
+        let selectors = pair
+            .into_inner()
+            .map(Selector::try_from)
+            .collect::<JsonPathResult<_>>()?;
+        Ok(Self {
+            descendant,
+            selectors,
+        })
+    }
+}
+
+impl TryFrom<Pair<'_, Rule>> for Selector {
+    type Error = JsonPathError;
+
+    fn try_from(pair: Pair<'_, Rule>) -> JsonPathResult<Self> {
+        let selector = match pair.as_rule() {
+            Rule::wildcard => Selector::Wildcard,
+            Rule::member_name => Selector::Name(pair.as_str().to_owned()),
+            Rule::name => Selector::Name(parse_name(pair)?),
+            Rule::index => Selector::Index(parse_int(pair)?),
+            Rule::slice => {
+                let (mut start, mut end, mut step) = (None, None, None);
+                for bound in pair.into_inner() {
+                    match bound.as_rule() {
+                        Rule::slice_start => start = Some(parse_int(bound)?),
+                        Rule::slice_end => end = Some(parse_int(bound)?),
+                        Rule::slice_step => step = Some(parse_int(bound)?),
+                        _ => unreachable!(),
+                    }
+                }
+                Selector::Slice { start, end, step }
+            }
+            Rule::filter => {
+                Selector::Filter(FilterExpr::try_from(pair.into_inner().next().unwrap())?)
+            }
+            _ => unreachable!(),
+        };
+        Ok(selector)
+    }
+}
+
+impl TryFrom<Pair<'_, Rule>> for FilterExpr {
+    type Error = JsonPathError;
+
+    fn try_from(pair: Pair<'_, Rule>) -> JsonPathResult<Self> {
+        let expr = match pair.as_rule() {
+            Rule::logical_or | Rule::logical_and => {
+                let rule = pair.as_rule();
+                let mut exprs = pair
+                    .into_inner()
+                    .map(FilterExpr::try_from)
+                    .collect::<JsonPathResult<Vec<_>>>()?;
+                match (exprs.len(), rule) {
+                    (1, _) => exprs.pop().unwrap(),
+                    (_, Rule::logical_or) => FilterExpr::Or(exprs),
+                    _ => FilterExpr::And(exprs),
+                }
+            }
+            Rule::paren_expr | Rule::test_expr => {
+                let mut inner = pair.into_inner().peekable();
+                let negated = inner
+                    .next_if(|pair| pair.as_rule() == Rule::not_op)
+                    .is_some();
+                let expr = FilterExpr::try_from(inner.next().unwrap())?;
+                if negated {
+                    FilterExpr::Not(Box::new(expr))
+                } else {
+                    expr
+                }
+            }
+            Rule::relative_query | Rule::root_query | Rule::function_expr => {
+                FilterExpr::Exists(parse_query(pair)?)
+            }
+            Rule::comparison => {
+                let mut inner = pair.into_inner();
+                let lhs = match parse_comparable(inner.next().unwrap())? {
+                    Operand::Literal(node) => Subject::Literal(node),
+                    Operand::Path(path) => Subject::Path(path),
+                    _ => unreachable!(),
+                };
+                let op = Op::from(inner.next().unwrap().as_str());
+                let rhs = parse_comparable(inner.next().unwrap())?;
+                // `!=` holds for the values that can't be compared, unlike the negated actor
+                if op == Op::Ne {
+                    let ast = Ast::Compare {
+                        lhs,
+                        op: Op::Eq,
+                        rhs,
+                    };
+                    FilterExpr::Not(Box::new(FilterExpr::Compare(ast)))
+                } else {
+                    FilterExpr::Compare(Ast::Compare { lhs, op, rhs })
+                }
+            }
+            _ => unreachable!(),
+        };
+        Ok(expr)
+    }
+}
+
+/// Query in a filter selector, relative to the value the filter is tested against.
+fn parse_query(pair: Pair<'_, Rule>) -> JsonPathResult<JsonPath> {
+    match pair.as_rule() {
+        Rule::relative_query => {
+            // relative to the value, which is the root of the query
+            let source = format!("${}", &pair.as_str()[1..]);
+            let segments = pair
+                .into_inner()
+                .map(Segment::try_from)
+                .collect::<JsonPathResult<_>>()?;
+            Ok(JsonPath { source, segments })
+        }
+        Rule::root_query => Err(JsonPathError::at(
+            &pair,
+            format!(
+                "queries from the root are not supported in filter selectors, use @ instead of $ in {}",
+                pair.as_str()
+            ),
+        )),
+        Rule::function_expr => Err(JsonPathError::at(
+            &pair,
+            format!(
+                "function extensions are not supported in filter selectors, use functions of the condition instead of {}",
+                pair.as_str()
+            ),
+        )),
+        _ => unreachable!(),
+    }
+}
+
+/// Literal or a singular query, the one that refers to at most one value.
+fn parse_comparable(pair: Pair<'_, Rule>) -> JsonPathResult<Operand<'static>> {
+    let node = match pair.as_rule() {
+        Rule::number => parse_number(pair)?,
+        Rule::name => DataNode::from(parse_name(pair)?),
+        Rule::true_literal => DataNode::Bool(true),
+        Rule::false_literal => DataNode::Bool(false),
+        Rule::null_literal => DataNode::Null,
+        _ => return singular_query_path(pair).map(Operand::Path),
+    };
+    Ok(Operand::Literal(node))
+}
+
+fn singular_query_path(pair: Pair<'_, Rule>) -> JsonPathResult<DataPath<'static>> {
+    let err = JsonPathError::at(
+        &pair,
+        format!(
+            "only singular queries can be compared, {} may refer to many values",
+            pair.as_str()
+        ),
+    );
+    let query = parse_query(pair)?;
+    let mut segments = Vec::new();
+    for segment in &query.segments {
+        match (segment.descendant, segment.selectors.as_slice()) {
+            (false, [Selector::Name(name)]) => {
+                segments.push(PathSegment::Key(Cow::Owned(name.clone())))
+            }
+            (false, [Selector::Index(index)]) => {
+                segments.extend(usize::try_from(*index).ok().map(PathSegment::Index))
+            }
+            _ => return Err(err),
+        }
+    }
+    // native paths can't count the indices from the end, so such a query stays JSONPath
+    if segments.len() < query.segments.len() {
+        return Ok(DataPath::Json(query));
+    }
+    Ok(DataPath::Native(Path::new(segments)))
+}
+
+/// Integers that don't fit into 64 bits are floats, same as the numbers with a fraction or an exponent.
+fn parse_number(pair: Pair<'_, Rule>) -> JsonPathResult<DataNode<'static>> {
+    let number = pair.as_str();
+    if let Ok(int) = number.parse::<i64>() {
+        return Ok(int.into());
+    }
+    number
+        .parse::<f64>()
+        .map(DataNode::from)
+        .map_err(|_| JsonPathError::at(&pair, format!("{number} is out of range")))
+}
+
+/// Unescaped name in quotes, which is also how the string literals are written.
+fn parse_name(pair: Pair<'_, Rule>) -> JsonPathResult<String> {
+    let quoted = pair.into_inner().next().unwrap();
+    let name = match quoted.as_rule() {
+        // `\'` is the only escape sequence that is specific to the single quoted names
+        Rule::single_quoted => {
+            unescape(&quoted.as_str().replace("\\'", "'")).map(|name| name.into_owned())
+        }
+        _ => unescape(quoted.as_str()).map(|name| name.into_owned()),
+    };
+    name.map_err(|err| JsonPathError::at(&quoted, format!("{} in {}", err.reason, quoted.as_str())))
+}
+
+fn parse_int(pair: Pair<'_, Rule>) -> JsonPathResult<i64> {
+    pair.as_str()
+        .parse()
+        .map_err(|_| JsonPathError::at(&pair, format!("{} is out of range", pair.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(JsonPath::new("$").unwrap().segments, vec![]);
+        assert_eq!(
+            JsonPath::new(r#"$.a..b[ 'c d' , "e\"f", 'g\'h' ]..*[*]"#)
+                .unwrap()
+                .segments,
+            vec![
+                Segment {
+                    descendant: false,
+                    selectors: vec![Selector::Name("a".into())],
+                },
+                Segment {
+                    descendant: true,
+                    selectors: vec![Selector::Name("b".into())],
+                },
+                Segment {
+                    descendant: false,
+                    selectors: vec![
+                        Selector::Name("c d".into()),
+                        Selector::Name("e\"f".into()),
+                        Selector::Name("g'h".into()),
+                    ],
+                },
+                Segment {
+                    descendant: true,
+                    selectors: vec![Selector::Wildcard],
+                },
+                Segment {
+                    descendant: false,
+                    selectors: vec![Selector::Wildcard],
+                },
+            ]
+        );
+        assert_eq!(
+            JsonPath::new("$[0, -1, 1:, :-1:2, ::-1, :]")
+                .unwrap()
+                .segments,
+            vec![Segment {
+                descendant: false,
+                selectors: vec![
+                    Selector::Index(0),
+                    Selector::Index(-1),
+                    Selector::Slice {
+                        start: Some(1),
+                        end: None,
+                        step: None
+                    },
+                    Selector::Slice {
+                        start: None,
+                        end: Some(-1),
+                        step: Some(2)
+                    },
+                    Selector::Slice {
+                        start: None,
+                        end: None,
+                        step: Some(-1)
+                    },
+                    Selector::Slice {
+                        start: None,
+                        end: None,
+                        step: None
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for inp in [
+            "",
+            "a",
+            "$a",
+            "$.1",
+            "$[]",
+            "$[01]",
+            "$[-0]",
+            "$['a]",
+            "$.a[0",
+            "$...a",
+            r"$['\q']",
+            "$[99999999999999999999]",
+        ] {
+            assert!(
+                matches!(JsonPath::new(inp), Err(FilsonError::PathParseError(_))),
+                "{inp}"
+            );
+        }
+        for (inp, message) in [
+            (
+                "$[?@.a == $.b]",
+                "queries from the root are not supported in filter selectors, use @ instead of $ in $.b at 10..13",
+            ),
+            (
+                "$[?length(@.a) > 1]",
+                "function extensions are not supported in filter selectors, use functions of the condition instead of length(@.a) at 3..14",
+            ),
+            (
+                "$[?@.a[*] == 1]",
+                "only singular queries can be compared, @.a[*] may refer to many values at 3..9",
+            ),
+            (
+                "$[?@..a == 1]",
+                "only singular queries can be compared, @..a may refer to many values at 3..7",
+            ),
+        ] {
+            assert_eq!(
+                JsonPath::new(inp),
+                Err(FilsonError::PathParseError(message.into())),
+                "{inp}"
+            );
+        }
+        for inp in [
+            "$[?]",
+            "$[?@.a ==]",
+            "$[?(@.a]",
+            "$[?@.a = 1]",
+            "$[?1]",
+            "$[?@.a && ]",
+        ] {
+            assert!(
+                matches!(JsonPath::new(inp), Err(FilsonError::PathParseError(_))),
+                "{inp}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let filter = |inp: &str| {
+            let mut segments = JsonPath::new(inp).unwrap().segments;
+            match segments.pop().unwrap().selectors.pop().unwrap() {
+                Selector::Filter(expr) => expr,
+                selector => panic!("expected a filter, got {selector:?}"),
+            }
+        };
+        let compare = |lhs, op, rhs| FilterExpr::Compare(Ast::Compare { lhs, op, rhs });
+        let path = |segments| DataPath::Native(Path::new(segments));
+        let key = |key: &'static str| PathSegment::Key(key.into());
+        assert_eq!(
+            filter("$.a[?(@.b[0] < 1)]"),
+            compare(
+                Subject::Path(path(vec![key("b"), PathSegment::Index(0)])),
+                Op::Lt,
+                Operand::Literal(1.into())
+            )
+        );
+        assert_eq!(
+            filter(r#"$[?@['c d'] != "x" || !@.e && 1.5e1 >= @ || !(@[-1] == null)]"#),
+            FilterExpr::Or(vec![
+                FilterExpr::Not(Box::new(compare(
+                    Subject::Path(path(vec![key("c d")])),
+                    Op::Eq,
+                    Operand::Literal("x".into())
+                ))),
+                FilterExpr::And(vec![
+                    FilterExpr::Not(Box::new(FilterExpr::Exists(JsonPath {
+                        source: "$.e".into(),
+                        segments: vec![Segment {
+                            descendant: false,
+                            selectors: vec![Selector::Name("e".into())]
+                        }]
+                    }))),
+                    compare(
+                        Subject::Literal(15.0.into()),
+                        Op::Gte,
+                        Operand::Path(path(vec![]))
+                    ),
+                ]),
+                FilterExpr::Not(Box::new(compare(
+                    Subject::Path(DataPath::Json(JsonPath::new("$[-1]").unwrap())),
+                    Op::Eq,
+                    Operand::Literal(DataNode::Null)
+                ))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_slice_indices() {
+        let indices = |start, end, step| slice_indices(5, start, end, step).collect::<Vec<_>>();
+        assert_eq!(indices(None, None, None), vec![0, 1, 2, 3, 4]);
+        assert_eq!(indices(Some(1), Some(3), None), vec![1, 2]);
+        assert_eq!(indices(Some(-2), None, None), vec![3, 4]);
+        assert_eq!(indices(None, None, Some(2)), vec![0, 2, 4]);
+        assert_eq!(indices(None, None, Some(-1)), vec![4, 3, 2, 1, 0]);
+        assert_eq!(indices(Some(3), Some(0), Some(-2)), vec![3, 1]);
+        assert_eq!(indices(Some(-10), Some(10), None), vec![0, 1, 2, 3, 4]);
+        assert_eq!(indices(Some(3), Some(1), None), Vec::<i64>::new());
+        assert_eq!(indices(None, None, Some(0)), Vec::<i64>::new());
+        assert_eq!(indices(Some(1), None, Some(i64::MAX)), vec![1]);
+        assert_eq!(indices(None, None, Some(i64::MIN)), vec![4]);
+        assert_eq!(slice_indices(0, None, None, Some(-1)).count(), 0);
+    }
+}
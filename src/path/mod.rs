@@ -0,0 +1,152 @@
+mod json_path;
+mod native;
+
+use std::borrow::Cow;
+
+use crate::{Extractable, FilsonError, FilsonResult};
+
+pub use json_path::JsonPath;
+pub use native::{Path, PathSegment};
+
+/// Tree-shaped data, such as json, that paths can be resolved in.
+///
+/// Implementing it is enough to support both [json pointers](https://www.rfc-editor.org/rfc/rfc6901),
+/// including the `*` wildcard, and [JSONPath](https://www.rfc-editor.org/rfc/rfc9535) in [Extractable](crate::Extractable).
+/// Comparisons in JSONPath filter selectors extract the values from the children with [Extractable::extract_path],
+/// that's why every [Navigable] is [Extractable] as well.
+///
+/// ```rust
+/// use filson::{get_filter, Appliable, DataNode, Extractable, FilsonError, Navigable};
+///
+/// enum Tree {
+///     Leaf(i64),
+///     Branch(Vec<(String, Tree)>),
+/// }
+///
+/// impl Navigable for Tree {
+///     fn member(&self, name: &str) -> Option<&Self> {
+///         match self {
+///             Tree::Branch(children) => children.iter().find(|(key, _)| key == name).map(|(_, child)| child),
+///             Tree::Leaf(_) => None,
+///         }
+///     }
+///
+///     fn elements(&self) -> Option<&[Self]> {
+///         None
+///     }
+///
+///     fn children(&self) -> Vec<&Self> {
+///         match self {
+///             Tree::Branch(children) => children.iter().map(|(_, child)| child).collect(),
+///             Tree::Leaf(_) => Vec::new(),
+///         }
+///     }
+/// }
+///
+/// impl Extractable for Tree {
+///     fn extract(&self, path: &str) -> Result<DataNode, FilsonError> {
+///         match self.select(path)?.as_slice() {
+///             [Tree::Leaf(value)] => Ok((*value).into()),
+///             _ => Err(FilsonError::ExtractionError),
+///         }
+///     }
+///
+///     fn extract_all(&self, path: &str) -> Result<Vec<DataNode>, FilsonError> {
+///         self.select(path)?
+///             .into_iter()
+///             .map(|node| match node {
+///                 Tree::Leaf(value) => Ok((*value).into()),
+///                 Tree::Branch(_) => Err(FilsonError::TypeError),
+///             })
+///             .collect()
+///     }
+/// }
+///
+/// let tree = Tree::Branch(vec![
+///     ("a".into(), Tree::Leaf(1)),
+///     ("b".into(), Tree::Branch(vec![("c".into(), Tree::Leaf(2))])),
+/// ]);
+/// let flt = get_filter(r#"compare("/b/c" == 2) && compare("$..c" == 2)"#).unwrap();
+/// assert_eq!(flt.apply(&tree), Ok(true));
+/// ```
+pub trait Navigable: Extractable + Sized {
+    /// Value of the member `name`, if this is an object.
+    fn member(&self, name: &str) -> Option<&Self>;
+
+    /// Elements in order, if this is an array.
+    fn elements(&self) -> Option<&[Self]>;
+
+    /// Every value of an object or every element of an array, in order.
+    fn children(&self) -> Vec<&Self>;
+
+    /// Values the `path` refers to, which is JSONPath if it starts with `$`, otherwise a json pointer.
+    ///
+    /// A `*` in place of a reference token in a json pointer refers to every child of the value,
+    /// a member literally named `*` is escaped as `~2`.
+    /// Values that lack the rest of the path are skipped, it fails only if none of them have it.
+    fn select(&self, path: &str) -> FilsonResult<Vec<&Self>> {
+        let values = if path.starts_with('$') {
+            path.parse::<JsonPath>()?.select(self)
+        } else {
+            select_by_pointer(self, path)?
+        };
+        non_empty(values)
+    }
+}
+
+/// Paths that refer to no values fail to extract.
+pub(crate) fn non_empty<T>(values: Vec<T>) -> FilsonResult<Vec<T>> {
+    if values.is_empty() {
+        return Err(FilsonError::ExtractionError);
+    }
+    Ok(values)
+}
+
+fn select_by_pointer<'v, N: Navigable>(root: &'v N, path: &str) -> FilsonResult<Vec<&'v N>> {
+    if path.is_empty() {
+        return Ok(vec![root]);
+    }
+    let tokens = path.strip_prefix('/').ok_or(FilsonError::ExtractionError)?;
+    let mut values = vec![root];
+    for token in tokens.split('/') {
+        values = if token == "*" {
+            values.into_iter().flat_map(N::children).collect()
+        } else {
+            values
+                .into_iter()
+                .filter_map(|value| pointer_child(value, token))
+                .collect()
+        };
+    }
+    Ok(values)
+}
+
+/// Child by an escaped json pointer reference token, a member of an object or an element of an array.
+///
+/// Besides `~0` and `~1` of RFC 6901, `~2` stands for `*`, that is otherwise a wildcard.
+pub(crate) fn pointer_child<'v, N: Navigable>(node: &'v N, token: &str) -> Option<&'v N> {
+    // `~0` goes last, so that `~02` is `~2` rather than `*`
+    let token = if token.contains('~') {
+        Cow::Owned(
+            token
+                .replace("~1", "/")
+                .replace("~2", "*")
+                .replace("~0", "~"),
+        )
+    } else {
+        Cow::Borrowed(token)
+    };
+    match node.elements() {
+        Some(elements) => parse_index(&token).and_then(|i| elements.get(i)),
+        None => node.member(&token),
+    }
+}
+
+/// Same as in [serde_json](https://docs.rs/serde_json/latest/serde_json/enum.Value.html#method.pointer),
+/// neither signs nor leading zeros are allowed.
+fn parse_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() != 1) {
+        return None;
+    }
+    token.parse().ok()
+}
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+
+use pest::iterators::Pair;
+
+use crate::{
+    parser::{error_at, parse_string, Rule},
+    path::Navigable,
+    FilsonError, FilsonResult,
+};
+
+/// Path in the Filson-native syntax, e.g. `a.b[0]."weird key"[*]`.
+///
+/// Unlike the paths in strings, it is parsed once, when the filter is built,
+/// and passed to [Extractable::extract_path](crate::Extractable::extract_path) as is, so it is never split again.
+///
+/// The first key is an identifier (letters, digits and `_`, that doesn't start with a digit),
+/// the rest of the keys are either identifiers after a `.` or strings after a `.` or in brackets.
+/// Indices and `*`, that refers to every child of a value, are in brackets.
+/// `true`, `false` and `null` are values, so they can't be the first key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Path<'a> {
+    segments: Vec<PathSegment<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+    /// Member of an object, e.g. `a` or `"weird key"`.
+    Key(Cow<'a, str>),
+    /// Element of an array, e.g. `[0]`.
+    Index(usize),
+    /// Every child of a value, `[*]`.
+    Wildcard,
+}
+
+impl<'a> Path<'a> {
+    pub fn new(segments: Vec<PathSegment<'a>>) -> Self {
+        Self { segments }
+    }
+
+    pub fn segments(&self) -> &[PathSegment<'a>] {
+        &self.segments
+    }
+
+    /// The same path as a [json pointer](https://www.rfc-editor.org/rfc/rfc6901), e.g. `/a/b/0/weird key/*`.
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            pointer.push('/');
+            match segment {
+                PathSegment::Key(key) if key == "*" => pointer.push_str("~2"),
+                PathSegment::Key(key) => {
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"))
+                }
+                PathSegment::Index(index) => pointer.push_str(&index.to_string()),
+                PathSegment::Wildcard => pointer.push('*'),
+            }
+        }
+        pointer
+    }
+
+    /// Converts the path into one that owns all of its keys, detaching it from the input it was parsed from.
+    pub fn into_owned(self) -> Path<'static> {
+        let segments = self
+            .segments
+            .into_iter()
+            .map(|segment| match segment {
+                PathSegment::Key(key) => PathSegment::Key(Cow::Owned(key.into_owned())),
+                PathSegment::Index(index) => PathSegment::Index(index),
+                PathSegment::Wildcard => PathSegment::Wildcard,
+            })
+            .collect();
+        Path { segments }
+    }
+
+    /// Values the path refers to, values that lack the rest of the path are skipped.
+    /// Fails only if none of them have it.
+    pub fn select<'v, N: Navigable>(&self, root: &'v N) -> FilsonResult<Vec<&'v N>> {
+        let mut values = vec![root];
+        for segment in &self.segments {
+            values = match segment {
+                PathSegment::Key(key) => values
+                    .into_iter()
+                    .filter_map(|value| value.member(key))
+                    .collect(),
+                PathSegment::Index(index) => values
+                    .into_iter()
+                    .filter_map(|value| value.elements()?.get(*index))
+                    .collect(),
+                PathSegment::Wildcard => values.into_iter().flat_map(N::children).collect(),
+            };
+        }
+        if values.is_empty() {
+            return Err(FilsonError::ExtractionError);
+        }
+        Ok(values)
+    }
+}
+
+impl<'a> TryFrom<Pair<'a, Rule>> for Path<'a> {
+    type Error = FilsonError;
+
+    fn try_from(pair: Pair<'a, Rule>) -> FilsonResult<Self> {
+        let segments =
+            pair.into_inner()
+                .map(|segment| {
+                    let segment = match segment.as_rule() {
+                        Rule::path_identifier => PathSegment::Key(segment.as_str().into()),
+                        Rule::string => PathSegment::Key(parse_string(segment)?),
+                        Rule::path_index => {
+                            PathSegment::Index(segment.as_str().parse().map_err(|_| {
+                                error_at(segment.as_span(), "index is out of range")
+                            })?)
+                        }
+                        Rule::path_wildcard => PathSegment::Wildcard,
+                        _ => unreachable!(),
+                    };
+                    Ok(segment)
+                })
+                .collect::<FilsonResult<_>>()?;
+        Ok(Self { segments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_pointer() {
+        assert_eq!(Path::new(vec![]).to_pointer(), "");
+        assert_eq!(
+            Path::new(vec![
+                PathSegment::Key("a".into()),
+                PathSegment::Index(0),
+                PathSegment::Key("~b/c d".into()),
+                PathSegment::Wildcard,
+                PathSegment::Key("*".into()),
+                PathSegment::Key("~2*".into()),
+            ])
+            .to_pointer(),
+            "/a/0/~0b~1c d/*/~2/~02*"
+        );
+    }
+}
@@ -1,12 +1,13 @@
-use crate::{Extractable, FilsonResult};
+use crate::{Clock, Extractable, FilsonResult, Params, SystemClock};
 
 pub trait Appliable: Send {
     /// Most likely you will never have to implement it yourself.
     /// But for completeness sake, let's build on the `Quickstart` example.
     ///
     /// ```rust
+    /// # #[cfg(feature = "serde_json")] {
     /// use serde_json::json;
-    /// use filson::{Appliable, Extractable, DataNode, get_filter, FilsonError};
+    /// use filson::{Appliable, Clock, Extractable, DataNode, get_filter, FilsonError, Params};
     ///
     /// struct TogglableFilter<T: Appliable> {
     ///     is_on: bool,
@@ -22,12 +23,18 @@ pub trait Appliable: Send {
     /// // Technically, you don't have to implement Appliable for your type.
     /// // But you might want to do it if you need to re-export the Appliable trait.
     /// impl<T: Appliable> Appliable for TogglableFilter<T> {
-    ///     fn apply<Y: Extractable>(&self, v: &Y) -> Result<bool, FilsonError> {
+    ///     // the rest of the methods are provided and delegate to this one
+    ///     fn apply_with_params_and_clock<Y: Extractable, C: Clock>(
+    ///         &self,
+    ///         v: &Y,
+    ///         params: &Params,
+    ///         clock: &C,
+    ///     ) -> Result<bool, FilsonError> {
     ///         // you can do whatever you like in here
     ///         // for example query some data from db
     ///         // and then use it
     ///         // or perform arbitraty input data transformations
-    ///         Ok(self.is_on && self.flt.apply(v)?)
+    ///         Ok(self.is_on && self.flt.apply_with_params_and_clock(v, params, clock)?)
     ///     }
     /// }
     ///
@@ -56,6 +63,29 @@ pub trait Appliable: Send {
     ///             .collect::<Vec<_>>();
     ///
     /// assert_eq!(res, Vec::<serde_json::Value>::new());
+    /// # }
     /// ```
-    fn apply<T: Extractable>(&self, v: &T) -> FilsonResult<bool>;
+    fn apply<T: Extractable>(&self, v: &T) -> FilsonResult<bool> {
+        self.apply_with_clock(v, &SystemClock)
+    }
+
+    /// Same as [apply](Appliable::apply), but `now()` in the condition is read from the `clock`.
+    /// See [Clock] for examples.
+    fn apply_with_clock<T: Extractable, C: Clock>(&self, v: &T, clock: &C) -> FilsonResult<bool> {
+        self.apply_with_params_and_clock(v, &Params::new(), clock)
+    }
+
+    /// Same as [apply](Appliable::apply), but the `$name` parameters in the condition are bound to the `params`.
+    /// See [Params] for examples.
+    fn apply_with<T: Extractable>(&self, v: &T, params: &Params) -> FilsonResult<bool> {
+        self.apply_with_params_and_clock(v, params, &SystemClock)
+    }
+
+    /// Same as [apply_with](Appliable::apply_with), but `now()` in the condition is read from the `clock`.
+    fn apply_with_params_and_clock<T: Extractable, C: Clock>(
+        &self,
+        v: &T,
+        params: &Params,
+        clock: &C,
+    ) -> FilsonResult<bool>;
 }
@@ -0,0 +1,47 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Source of the current time for `now()` in filtration conditions.
+///
+/// [Appliable::apply](crate::Appliable::apply) reads the time from [SystemClock].
+/// Pass your own clock to [Appliable::apply_with_clock](crate::Appliable::apply_with_clock)
+/// to evaluate relative conditions at any other point in time, e.g. to make tests deterministic.
+///
+/// Any [DateTime] is a clock that is stopped at that instant.
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use chrono::DateTime;
+/// use serde_json::json;
+/// use filson::{get_filter, Appliable};
+///
+/// let flt = get_filter(r#"compare("/created_at" > now() - 7d)"#).unwrap();
+/// let data = json!({"created_at": "2024-01-01T00:00:00Z"});
+///
+/// let clock = DateTime::parse_from_rfc3339("2024-01-05T00:00:00Z").unwrap();
+/// assert!(flt.apply_with_clock(&data, &clock).unwrap());
+///
+/// let clock = DateTime::parse_from_rfc3339("2024-01-10T00:00:00Z").unwrap();
+/// assert!(!flt.apply_with_clock(&data, &clock).unwrap());
+/// # }
+/// ```
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Reads the current time from the system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        SystemTime::now().into()
+    }
+}
+
+impl<Tz: TimeZone> Clock for DateTime<Tz> {
+    fn now(&self) -> DateTime<Utc> {
+        self.with_timezone(&Utc)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{DataNode, FilsonResult};
+use crate::{DataNode, FilsonResult, JsonPath, Path};
 
 /// Filson can run comparisons over any data types, as long as they implement [Extractable].
 pub trait Extractable {
@@ -41,6 +41,8 @@ pub trait Extractable {
     ///             "make" => Ok(self.make.into()),
     ///             // DataNode only accepts i64 integers so the explicit conversion is required
     ///             "age" => Ok((self.age as i64).into()),
+    ///             // Computed values are fine too, DataNode can own its strings
+    ///             "make_lowercase" => Ok(self.make.to_lowercase().into()),
     ///             // We don't need to be able to extract milage field
     ///             _ => Err(FilsonError::ExtractionError),
     ///         }
@@ -49,7 +51,7 @@ pub trait Extractable {
     ///
     /// // Now we ready to build the Filson query and run it over our data
     /// // Remember, that query may be known only at runtime
-    /// let query = r#"and(compare("make" == "Volvo"), compare("age" < 5))"#;
+    /// let query = r#"and(compare("make_lowercase" == "volvo"), compare("age" < 5))"#;
     /// let flt = get_filter(query).unwrap();
     ///
     /// let filtered_cars = cars
@@ -73,5 +75,93 @@ pub trait Extractable {
     ///     ]
     /// );
     /// ```
-    fn extract(&self, path: &str) -> FilsonResult<DataNode>;
+    fn extract(&self, path: &str) -> FilsonResult<DataNode<'_>>;
+
+    /// Paths may refer to many values at once, e.g. `/items/*/price` refers to the price of every item.
+    /// Filson extracts the data with this method, and an actor holds if it holds for any of the values.
+    ///
+    /// By default a path refers to a single value, the one returned by [extract](Extractable::extract).
+    /// Implementations should fail with [FilsonError::ExtractionError](crate::FilsonError::ExtractionError), rather than return no values.
+    ///
+    /// ```rust
+    /// use filson::{get_filter, Appliable, DataNode, Extractable, FilsonError};
+    ///
+    /// struct Order {
+    ///     prices: Vec<u32>,
+    /// }
+    ///
+    /// impl Extractable for Order {
+    ///     fn extract(&self, path: &str) -> Result<DataNode, FilsonError> {
+    ///         match path {
+    ///             "prices" => Ok(self.prices.iter().map(|p| (*p).into()).collect::<Vec<_>>().into()),
+    ///             _ => Err(FilsonError::ExtractionError),
+    ///         }
+    ///     }
+    ///
+    ///     fn extract_all(&self, path: &str) -> Result<Vec<DataNode>, FilsonError> {
+    ///         match path {
+    ///             "price" if !self.prices.is_empty() => Ok(self.prices.iter().map(|p| (*p).into()).collect()),
+    ///             _ => self.extract(path).map(|node| vec![node]),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let order = Order { prices: vec![10, 150] };
+    /// let flt = get_filter(r#"compare("price" > 100)"#).unwrap();
+    /// assert_eq!(flt.apply(&order), Ok(true));
+    /// let flt = get_filter(r#"compare(len("prices") > 2)"#).unwrap();
+    /// assert_eq!(flt.apply(&order), Ok(false));
+    /// ```
+    fn extract_all(&self, path: &str) -> FilsonResult<Vec<DataNode<'_>>> {
+        self.extract(path).map(|node| vec![node])
+    }
+
+    /// Filson extracts the data by the paths written in the native syntax, e.g. `owner.name`, with this method.
+    /// They are parsed when the filter is built, so the segments can be matched on, without splitting strings.
+    ///
+    /// By default the path is converted to a json pointer, e.g. `/owner/name`, and passed to [extract_all](Extractable::extract_all).
+    ///
+    /// ```rust
+    /// use filson::{get_filter, Appliable, DataNode, Extractable, FilsonError, Path, PathSegment};
+    ///
+    /// struct Car {
+    ///     make: &'static str,
+    ///     owner: &'static str,
+    /// }
+    ///
+    /// impl Extractable for Car {
+    ///     fn extract(&self, path: &str) -> Result<DataNode, FilsonError> {
+    ///         match path {
+    ///             "make" => Ok(self.make.into()),
+    ///             _ => Err(FilsonError::ExtractionError),
+    ///         }
+    ///     }
+    ///
+    ///     fn extract_path(&self, path: &Path) -> Result<Vec<DataNode>, FilsonError> {
+    ///         match path.segments() {
+    ///             [PathSegment::Key(key)] => self.extract(key).map(|node| vec![node]),
+    ///             [PathSegment::Key(owner), PathSegment::Key(name)] if *owner == "owner" && *name == "name" => {
+    ///                 Ok(vec![self.owner.into()])
+    ///             }
+    ///             _ => Err(FilsonError::ExtractionError),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let car = Car { make: "Volvo", owner: "Ann" };
+    /// let flt = get_filter(r#"compare(make == "Volvo") && compare(owner.name == "Ann")"#).unwrap();
+    /// assert_eq!(flt.apply(&car), Ok(true));
+    /// ```
+    fn extract_path(&self, path: &Path) -> FilsonResult<Vec<DataNode<'_>>> {
+        self.extract_all(&path.to_pointer())
+    }
+
+    /// Filson extracts the data by the [JSONPath](https://www.rfc-editor.org/rfc/rfc9535) strings, e.g. `"$..price"`, with this method.
+    /// They are parsed when the filter is built, so a [Navigable](crate::Navigable) implementation can select the values
+    /// with [JsonPath::select] instead of parsing the path on every extraction.
+    ///
+    /// By default the path is passed to [extract_all](Extractable::extract_all) as it was written.
+    fn extract_json_path(&self, path: &JsonPath) -> FilsonResult<Vec<DataNode<'_>>> {
+        self.extract_all(path.as_str())
+    }
 }
@@ -1,5 +1,7 @@
 mod appliable;
+mod clock;
 mod extractable;
 
 pub use crate::traits::appliable::Appliable;
+pub use crate::traits::clock::{Clock, SystemClock};
 pub use crate::traits::extractable::Extractable;
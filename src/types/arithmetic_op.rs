@@ -0,0 +1,21 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum ArithmeticOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl<T: AsRef<str>> From<T> for ArithmeticOp {
+    fn from(value: T) -> Self {
+        match value.as_ref() {
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            "%" => Self::Rem,
+            _ => unreachable!(),
+        }
+    }
+}
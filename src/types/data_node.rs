@@ -1,31 +1,65 @@
 use std::{
+    borrow::Cow,
+    cmp::Ordering,
     collections::{BTreeMap, BTreeSet},
-    mem::discriminant,
+    hash::{Hash, Hasher},
+    time::SystemTime,
 };
 
 use crate::{
     error::FilsonResult,
-    parser::{parse_float, parse_int, Rule},
+    parser::{
+        parse_datetime, parse_decimal_literal, parse_duration, parse_duration_literal,
+        parse_float_literal, parse_int_literal, parse_string, Rule,
+    },
+    types::ArithmeticOp,
     FilsonError,
 };
 
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
 use ordered_float::OrderedFloat;
 use pest::iterators::Pair;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 
-#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Hash)]
+/// Strings may be either borrowed from the data they were extracted from or owned,
+/// so [Extractable](crate::Extractable) implementations are free to return computed values.
+///
+/// Datetimes keep their offset, but are compared (and hashed) by the instant in time they represent.
+///
+/// Integers of different widths are the same type, they are compared (and hashed) by their value.
+/// Decimals are compared exactly, so `1.50` and `1.5` are equal.
+#[derive(Debug, Clone)]
 pub enum DataNode<'a> {
     Map(BTreeMap<DataNode<'a>, DataNode<'a>>),
     Set(BTreeSet<DataNode<'a>>),
     Array(Vec<DataNode<'a>>),
     I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
     F64(OrderedFloat<f64>),
-    Str(&'a str),
+    Decimal(Decimal),
+    Str(Cow<'a, str>),
+    DateTime(DateTime<FixedOffset>),
+    Duration(Duration),
     Bool(bool),
     Null,
 }
 
 impl<'a> From<&'a str> for DataNode<'a> {
     fn from(s: &'a str) -> Self {
+        Self::Str(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for DataNode<'_> {
+    fn from(s: String) -> Self {
+        Self::Str(Cow::Owned(s))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for DataNode<'a> {
+    fn from(s: Cow<'a, str>) -> Self {
         Self::Str(s)
     }
 }
@@ -36,10 +70,32 @@ impl From<bool> for DataNode<'_> {
     }
 }
 
-impl From<i64> for DataNode<'_> {
-    fn from(i: i64) -> Self {
-        Self::I64(i)
-    }
+macro_rules! impl_from_int {
+    ($($int:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$int> for DataNode<'_> {
+                fn from(i: $int) -> Self {
+                    // can't fail, every integer is converted into the variant that is at least as wide
+                    Self::$variant(i.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int! {
+    i8 => I64,
+    i16 => I64,
+    i32 => I64,
+    i64 => I64,
+    isize => I64,
+    u8 => I64,
+    u16 => I64,
+    u32 => I64,
+    u64 => U64,
+    usize => U64,
+    i128 => I128,
+    u128 => U128,
 }
 
 impl From<f64> for DataNode<'_> {
@@ -48,6 +104,30 @@ impl From<f64> for DataNode<'_> {
     }
 }
 
+impl From<Decimal> for DataNode<'_> {
+    fn from(d: Decimal) -> Self {
+        Self::Decimal(d)
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for DataNode<'_> {
+    fn from(dt: DateTime<Tz>) -> Self {
+        Self::DateTime(dt.fixed_offset())
+    }
+}
+
+impl From<Duration> for DataNode<'_> {
+    fn from(d: Duration) -> Self {
+        Self::Duration(d)
+    }
+}
+
+impl From<SystemTime> for DataNode<'_> {
+    fn from(t: SystemTime) -> Self {
+        DateTime::<Utc>::from(t).into()
+    }
+}
+
 impl<'a, T> From<Option<T>> for DataNode<'a>
 where
     T: Into<DataNode<'a>>,
@@ -80,32 +160,37 @@ impl<'a> From<BTreeMap<DataNode<'a>, DataNode<'a>>> for DataNode<'a> {
 }
 
 //  unwraping is ok because parser won't allow for unsupported formats
-impl<'a> From<Pair<'a, Rule>> for DataNode<'a> {
-    fn from(pair: Pair<'a, Rule>) -> Self {
-        match pair.as_rule() {
+impl<'a> TryFrom<Pair<'a, Rule>> for DataNode<'a> {
+    type Error = FilsonError;
+
+    fn try_from(pair: Pair<'a, Rule>) -> FilsonResult<Self> {
+        let node = match pair.as_rule() {
             Rule::boolean => (pair.as_str() == "true").into(),
 
             Rule::null => Self::Null,
 
-            Rule::string => {
-                let chars = pair.into_inner().next().unwrap();
-                chars.as_str().into()
-            }
+            Rule::string => parse_string(pair)?.into(),
+
+            Rule::datetime => parse_datetime(pair)?.into(),
+
+            Rule::duration => parse_duration_literal(pair)?.into(),
+
+            Rule::integer => parse_int_literal(pair)?,
 
-            Rule::integer => parse_int(pair.as_str()).unwrap().into(),
+            Rule::float => parse_float_literal(pair)?.into(),
 
-            Rule::float => parse_float(pair.as_str()).unwrap().into(),
+            Rule::decimal => parse_decimal_literal(pair)?.into(),
 
             Rule::array => pair
                 .into_inner()
-                .map(DataNode::from)
-                .collect::<Vec<_>>()
+                .map(DataNode::try_from)
+                .collect::<FilsonResult<Vec<_>>>()?
                 .into(),
 
             Rule::set => pair
                 .into_inner()
-                .map(DataNode::from)
-                .collect::<BTreeSet<_>>()
+                .map(DataNode::try_from)
+                .collect::<FilsonResult<BTreeSet<_>>>()?
                 .into(),
 
             Rule::map => pair
@@ -114,17 +199,63 @@ impl<'a> From<Pair<'a, Rule>> for DataNode<'a> {
                     let mut inner_map_pair = map_pair.into_inner();
                     let key = inner_map_pair.next().unwrap();
                     let value = inner_map_pair.next().unwrap();
-                    (DataNode::from(key), DataNode::from(value))
+                    Ok((DataNode::try_from(key)?, DataNode::try_from(value)?))
                 })
-                .collect::<BTreeMap<_, _>>()
+                .collect::<FilsonResult<BTreeMap<_, _>>>()?
                 .into(),
 
             _ => unreachable!(),
-        }
+        };
+        Ok(node)
     }
 }
 
 impl DataNode<'_> {
+    /// Converts the node into one that owns all of its strings, detaching it from the data it was extracted from.
+    pub fn into_owned(self) -> DataNode<'static> {
+        match self {
+            DataNode::Map(map) => DataNode::Map(
+                map.into_iter()
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect(),
+            ),
+            DataNode::Set(set) => {
+                DataNode::Set(set.into_iter().map(DataNode::into_owned).collect())
+            }
+            DataNode::Array(arr) => {
+                DataNode::Array(arr.into_iter().map(DataNode::into_owned).collect())
+            }
+            DataNode::I64(i) => DataNode::I64(i),
+            DataNode::U64(u) => DataNode::U64(u),
+            DataNode::I128(i) => DataNode::I128(i),
+            DataNode::U128(u) => DataNode::U128(u),
+            DataNode::F64(f) => DataNode::F64(f),
+            DataNode::Decimal(d) => DataNode::Decimal(d),
+            DataNode::Str(s) => DataNode::Str(Cow::Owned(s.into_owned())),
+            DataNode::DateTime(dt) => DataNode::DateTime(dt),
+            DataNode::Duration(d) => DataNode::Duration(d),
+            DataNode::Bool(b) => DataNode::Bool(b),
+            DataNode::Null => DataNode::Null,
+        }
+    }
+
+    /// Same node, with its strings borrowed from this one rather than copied.
+    pub(crate) fn as_borrowed(&self) -> DataNode<'_> {
+        match self {
+            DataNode::Map(map) => DataNode::Map(
+                map.iter()
+                    .map(|(key, value)| (key.as_borrowed(), value.as_borrowed()))
+                    .collect(),
+            ),
+            DataNode::Set(set) => DataNode::Set(set.iter().map(DataNode::as_borrowed).collect()),
+            DataNode::Array(arr) => {
+                DataNode::Array(arr.iter().map(DataNode::as_borrowed).collect())
+            }
+            DataNode::Str(s) => DataNode::Str(Cow::Borrowed(s)),
+            scalar => scalar.clone(),
+        }
+    }
+
     pub(crate) fn is_collection_type(&self) -> bool {
         matches!(
             self,
@@ -132,14 +263,263 @@ impl DataNode<'_> {
         )
     }
 
+    #[inline]
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            DataNode::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
     #[inline]
     fn is_string_type(&self) -> bool {
         matches!(self, DataNode::Str(_))
     }
 
+    /// Converts the node into the type of `other`, when it makes sense to do so implicitly.
+    ///
+    /// Namely, RFC 3339 strings are parsed into datetimes when compared against a datetime,
+    /// duration strings (e.g. `"PT1H"`) are parsed into durations when compared against a duration
+    /// and decimal strings (e.g. `"12.30"`) are parsed into decimals when compared against a decimal,
+    /// since most of the data formats (e.g. json) don't have dedicated types for them.
+    pub(crate) fn coerce_to_type_of(&self, other: &Self) -> Option<DataNode<'static>> {
+        match (self, other) {
+            (DataNode::Str(s), DataNode::DateTime(_)) => {
+                DateTime::parse_from_rfc3339(s).ok().map(DataNode::DateTime)
+            }
+            (DataNode::Str(s), DataNode::Duration(_)) => {
+                parse_duration(s).ok().map(DataNode::Duration)
+            }
+            (DataNode::Str(s), DataNode::Decimal(_)) => {
+                Decimal::from_str_exact(s).ok().map(DataNode::Decimal)
+            }
+            _ => None,
+        }
+    }
+
+    /// Integers of all widths share the same position.
+    fn type_order(&self) -> u8 {
+        match self {
+            DataNode::Map(_) => 0,
+            DataNode::Set(_) => 1,
+            DataNode::Array(_) => 2,
+            DataNode::I64(_) | DataNode::U64(_) | DataNode::I128(_) | DataNode::U128(_) => 3,
+            DataNode::F64(_) => 4,
+            DataNode::Decimal(_) => 5,
+            DataNode::Str(_) => 6,
+            DataNode::DateTime(_) => 7,
+            DataNode::Duration(_) => 8,
+            DataNode::Bool(_) => 9,
+            DataNode::Null => 10,
+        }
+    }
+
+    fn as_integer(&self) -> Option<Integer> {
+        let int = match *self {
+            DataNode::I64(i) => Integer::Signed(i.into()),
+            DataNode::U64(u) => Integer::Signed(u.into()),
+            DataNode::I128(i) => Integer::Signed(i),
+            DataNode::U128(u) => i128::try_from(u)
+                .map(Integer::Signed)
+                .unwrap_or(Integer::Unsigned(u)),
+            _ => return None,
+        };
+        Some(int)
+    }
+
+    /// Orders integers, floats and decimals by their numeric value, `None` if any of the nodes isn't a number.
+    ///
+    /// Decimals are compared with floats as floats, the rest of the comparisons are exact.
+    pub(crate) fn cmp_numerically(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (DataNode::F64(left), DataNode::F64(right)) => Some(left.cmp(right)),
+            (DataNode::Decimal(left), DataNode::Decimal(right)) => Some(left.cmp(right)),
+            (DataNode::Decimal(left), DataNode::F64(right)) => {
+                Some(OrderedFloat(left.to_f64()?).cmp(right))
+            }
+            (DataNode::F64(_), DataNode::Decimal(_)) => {
+                other.cmp_numerically(self).map(Ordering::reverse)
+            }
+            (DataNode::F64(left), _) => other
+                .as_integer()
+                .map(|right| right.cmp_to_float(left.0).reverse()),
+            (_, DataNode::F64(right)) => self.as_integer().map(|left| left.cmp_to_float(right.0)),
+            (DataNode::Decimal(left), _) => other
+                .as_integer()
+                .map(|right| right.cmp_to_decimal(left).reverse()),
+            (_, DataNode::Decimal(right)) => {
+                self.as_integer().map(|left| left.cmp_to_decimal(right))
+            }
+            _ => Some(self.as_integer()?.cmp(&other.as_integer()?)),
+        }
+    }
+
+    /// Applies the arithmetic operation to a pair of numbers, any other type yields [FilsonError::TypeError].
+    ///
+    /// Integers of any width stay integers, so the division truncates towards zero.
+    /// A float on either side makes the result a float, otherwise a decimal on either side makes it a decimal.
+    pub(crate) fn arithmetic(
+        &self,
+        op: ArithmeticOp,
+        other: &Self,
+    ) -> FilsonResult<DataNode<'static>> {
+        let is_number = |node: &Self| {
+            matches!(node, DataNode::F64(_) | DataNode::Decimal(_)) || node.as_integer().is_some()
+        };
+        if !is_number(self) || !is_number(other) {
+            return Err(FilsonError::TypeError);
+        }
+        let is_division = matches!(op, ArithmeticOp::Div | ArithmeticOp::Rem);
+        if matches!(self, DataNode::F64(_)) || matches!(other, DataNode::F64(_)) {
+            let (left, right) = (self.as_f64(), other.as_f64());
+            if is_division && right == 0.0 {
+                return Err(FilsonError::DivisionByZeroError);
+            }
+            let res = match op {
+                ArithmeticOp::Add => left + right,
+                ArithmeticOp::Sub => left - right,
+                ArithmeticOp::Mul => left * right,
+                ArithmeticOp::Div => left / right,
+                ArithmeticOp::Rem => left % right,
+            };
+            if res.is_infinite() && left.is_finite() && right.is_finite() {
+                return Err(FilsonError::OverflowError);
+            }
+            return Ok(res.into());
+        }
+        if matches!(self, DataNode::Decimal(_)) || matches!(other, DataNode::Decimal(_)) {
+            let (left, right) = (self.as_decimal()?, other.as_decimal()?);
+            if is_division && right.is_zero() {
+                return Err(FilsonError::DivisionByZeroError);
+            }
+            let res = match op {
+                ArithmeticOp::Add => left.checked_add(right),
+                ArithmeticOp::Sub => left.checked_sub(right),
+                ArithmeticOp::Mul => left.checked_mul(right),
+                ArithmeticOp::Div => left.checked_div(right),
+                ArithmeticOp::Rem => left.checked_rem(right),
+            };
+            return res.map(DataNode::from).ok_or(FilsonError::OverflowError);
+        }
+        // both are integers here
+        let res = self
+            .as_integer()
+            .unwrap()
+            .checked_arithmetic(op, other.as_integer().unwrap())?;
+        Ok(match res {
+            Integer::Signed(i) => DataNode::I128(i).normalize_width(),
+            Integer::Unsigned(u) => DataNode::U128(u),
+        })
+    }
+
+    /// Changes the sign of a number, any other type yields [FilsonError::TypeError].
+    pub(crate) fn negate(&self) -> FilsonResult<DataNode<'static>> {
+        DataNode::I64(0).arithmetic(ArithmeticOp::Sub, self)
+    }
+
+    /// Expects a number, integers and decimals may lose precision.
+    fn as_f64(&self) -> f64 {
+        match (self, self.as_integer()) {
+            (DataNode::F64(f), _) => f.0,
+            // can't fail, decimals fit into a float
+            (DataNode::Decimal(d), _) => d.to_f64().unwrap(),
+            (_, Some(Integer::Signed(i))) => i as f64,
+            (_, Some(Integer::Unsigned(u))) => u as f64,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Expects a decimal or an integer, the integers that don't fit into a decimal yield [FilsonError::OverflowError].
+    fn as_decimal(&self) -> FilsonResult<Decimal> {
+        match (self, self.as_integer()) {
+            (DataNode::Decimal(d), _) => Ok(*d),
+            (_, Some(Integer::Signed(i))) => {
+                Decimal::try_from_i128_with_scale(i, 0).map_err(|_| FilsonError::OverflowError)
+            }
+            (_, Some(Integer::Unsigned(_))) => Err(FilsonError::OverflowError),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Converts integral floats and decimals into the integers of the same value
+    /// and the rest of decimals into floats, when the float is written the same way,
+    /// including the ones inside of collections.
+    ///
+    /// Returns `None` if there is nothing to convert.
+    pub(crate) fn normalize_numbers(&self) -> Option<Self> {
+        let normalized = match self {
+            DataNode::F64(f) => integer_from_float(f.0)?,
+            DataNode::Decimal(d) if d.is_integer() => {
+                // can't fail, decimals fit into 96 bits
+                DataNode::I128(d.to_i128().unwrap()).normalize_width()
+            }
+            DataNode::Decimal(d) => {
+                let f = d.to_f64()?;
+                if Decimal::from_str_exact(&f.to_string()).ok()? != *d {
+                    return None;
+                }
+                f.into()
+            }
+            DataNode::Array(arr) => {
+                if !arr.iter().any(DataNode::has_floats) {
+                    return None;
+                }
+                DataNode::Array(arr.iter().map(DataNode::normalized_numbers).collect())
+            }
+            DataNode::Set(set) => {
+                if !set.iter().any(DataNode::has_floats) {
+                    return None;
+                }
+                DataNode::Set(set.iter().map(DataNode::normalized_numbers).collect())
+            }
+            DataNode::Map(map) => {
+                if !map
+                    .iter()
+                    .any(|(key, value)| key.has_floats() || value.has_floats())
+                {
+                    return None;
+                }
+                DataNode::Map(
+                    map.iter()
+                        .map(|(key, value)| (key.normalized_numbers(), value.normalized_numbers()))
+                        .collect(),
+                )
+            }
+            _ => return None,
+        };
+        Some(normalized)
+    }
+
+    /// Converts 128 bit integer into the narrowest variant it fits into.
+    fn normalize_width(self) -> Self {
+        match self {
+            DataNode::I128(i) => i64::try_from(i)
+                .map(DataNode::I64)
+                .or_else(|_| u64::try_from(i).map(DataNode::U64))
+                .unwrap_or(self),
+            _ => self,
+        }
+    }
+
+    fn normalized_numbers(&self) -> Self {
+        self.normalize_numbers().unwrap_or_else(|| self.clone())
+    }
+
+    fn has_floats(&self) -> bool {
+        match self {
+            DataNode::F64(_) | DataNode::Decimal(_) => true,
+            DataNode::Array(arr) => arr.iter().any(DataNode::has_floats),
+            DataNode::Set(set) => set.iter().any(DataNode::has_floats),
+            DataNode::Map(map) => map
+                .iter()
+                .any(|(key, value)| key.has_floats() || value.has_floats()),
+            _ => false,
+        }
+    }
+
     #[inline]
     pub(crate) fn error_on_type_mismatch(&self, other: &Self) -> FilsonResult<()> {
-        if discriminant(self) != discriminant(other) {
+        if self.type_order() != other.type_order() {
             return Err(crate::FilsonError::TypeError);
         };
         Ok(())
@@ -153,3 +533,170 @@ impl DataNode<'_> {
         Ok(())
     }
 }
+
+/// Integer of any width, normalized so that equal values have the same representation.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Integer {
+    Signed(i128),
+    /// Only the values that don't fit into `i128`.
+    Unsigned(u128),
+}
+
+impl Integer {
+    /// Exact comparison, unlike converting either side into the type of the other.
+    fn cmp_to_float(&self, f: f64) -> Ordering {
+        let approx = match *self {
+            Integer::Signed(i) => i as f64,
+            Integer::Unsigned(u) => u as f64,
+        };
+        // rounding is monotonic, so it can't reverse the order, but it may make different values equal
+        match OrderedFloat(approx).cmp(&OrderedFloat(f)) {
+            Ordering::Equal => match *self {
+                // `f` is integral here, but it may be rounded up to 2^127 or 2^128, just past the range
+                Integer::Signed(_) if f >= I128_END => Ordering::Less,
+                Integer::Signed(i) => i.cmp(&(f as i128)),
+                Integer::Unsigned(_) if f >= U128_END => Ordering::Less,
+                Integer::Unsigned(u) => u.cmp(&(f as u128)),
+            },
+            ordering => ordering,
+        }
+    }
+
+    /// Decimals fit into 96 bits, so the integers that don't fit into a decimal are greater by the absolute value.
+    fn cmp_to_decimal(&self, d: &Decimal) -> Ordering {
+        match *self {
+            Integer::Signed(i) => match Decimal::try_from_i128_with_scale(i, 0) {
+                Ok(i) => i.cmp(d),
+                Err(_) => i.cmp(&0),
+            },
+            Integer::Unsigned(_) => Ordering::Greater,
+        }
+    }
+
+    /// Exact for any pair of integers, as long as the result fits into either `i128` or `u128`.
+    fn checked_arithmetic(&self, op: ArithmeticOp, other: Integer) -> FilsonResult<Integer> {
+        let ((left_negative, left), (right_negative, right)) =
+            (self.to_sign_magnitude(), other.to_sign_magnitude());
+        let (negative, magnitude) = match op {
+            ArithmeticOp::Add | ArithmeticOp::Sub => {
+                let right_negative = right_negative ^ (op == ArithmeticOp::Sub);
+                if left_negative == right_negative {
+                    (left_negative, left.checked_add(right))
+                } else if left >= right {
+                    (left_negative, Some(left - right))
+                } else {
+                    (right_negative, Some(right - left))
+                }
+            }
+            ArithmeticOp::Mul => (left_negative ^ right_negative, left.checked_mul(right)),
+            ArithmeticOp::Div | ArithmeticOp::Rem if right == 0 => {
+                return Err(FilsonError::DivisionByZeroError)
+            }
+            ArithmeticOp::Div => (left_negative ^ right_negative, Some(left / right)),
+            // the remainder has the sign of the dividend
+            ArithmeticOp::Rem => (left_negative, Some(left % right)),
+        };
+        magnitude
+            .and_then(|magnitude| Integer::from_sign_magnitude(negative, magnitude))
+            .ok_or(FilsonError::OverflowError)
+    }
+
+    /// Whether the integer is negative and its absolute value.
+    fn to_sign_magnitude(&self) -> (bool, u128) {
+        match *self {
+            Integer::Signed(i) => (i < 0, i.unsigned_abs()),
+            Integer::Unsigned(u) => (false, u),
+        }
+    }
+
+    fn from_sign_magnitude(negative: bool, magnitude: u128) -> Option<Integer> {
+        match i128::try_from(magnitude) {
+            Ok(i) if negative => Some(Integer::Signed(-i)),
+            Ok(i) => Some(Integer::Signed(i)),
+            // 2^127, the only negative value with the absolute value that doesn't fit into `i128`
+            Err(_) if negative && magnitude == i128::MIN.unsigned_abs() => {
+                Some(Integer::Signed(i128::MIN))
+            }
+            Err(_) if negative => None,
+            Err(_) => Some(Integer::Unsigned(magnitude)),
+        }
+    }
+}
+
+/// 2^127, the first float past `i128::MAX`.
+const I128_END: f64 = 170141183460469231731687303715884105728.0;
+/// 2^128, the first float past `u128::MAX`.
+const U128_END: f64 = 340282366920938463463374607431768211456.0;
+
+pub(super) fn integer_from_float(f: f64) -> Option<DataNode<'static>> {
+    if f.fract() != 0.0 || !(-I128_END..U128_END).contains(&f) {
+        return None;
+    }
+    let node = if f >= I128_END {
+        DataNode::U128(f as u128)
+    } else if (i64::MIN as f64..I64_END).contains(&f) {
+        DataNode::I64(f as i64)
+    } else {
+        DataNode::I128(f as i128)
+    };
+    Some(node)
+}
+
+/// 2^63, the first float past `i64::MAX`.
+const I64_END: f64 = 9223372036854775808.0;
+
+/// Values of the different types are ordered by the position of the type in the [DataNode] declaration.
+impl Ord for DataNode<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (DataNode::Map(left), DataNode::Map(right)) => left.cmp(right),
+            (DataNode::Set(left), DataNode::Set(right)) => left.cmp(right),
+            (DataNode::Array(left), DataNode::Array(right)) => left.cmp(right),
+            (DataNode::F64(left), DataNode::F64(right)) => left.cmp(right),
+            (DataNode::Decimal(left), DataNode::Decimal(right)) => left.cmp(right),
+            (DataNode::Str(left), DataNode::Str(right)) => left.cmp(right),
+            (DataNode::DateTime(left), DataNode::DateTime(right)) => left.cmp(right),
+            (DataNode::Duration(left), DataNode::Duration(right)) => left.cmp(right),
+            (DataNode::Bool(left), DataNode::Bool(right)) => left.cmp(right),
+            _ => match (self.as_integer(), other.as_integer()) {
+                (Some(left), Some(right)) => left.cmp(&right),
+                _ => self.type_order().cmp(&other.type_order()),
+            },
+        }
+    }
+}
+
+impl PartialOrd for DataNode<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for DataNode<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DataNode<'_> {}
+
+impl Hash for DataNode<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_order().hash(state);
+        match self {
+            DataNode::Map(map) => map.hash(state),
+            DataNode::Set(set) => set.hash(state),
+            DataNode::Array(arr) => arr.hash(state),
+            DataNode::I64(_) | DataNode::U64(_) | DataNode::I128(_) | DataNode::U128(_) => {
+                self.as_integer().hash(state)
+            }
+            DataNode::F64(f) => f.hash(state),
+            DataNode::Decimal(d) => d.hash(state),
+            DataNode::Str(s) => s.hash(state),
+            DataNode::DateTime(dt) => dt.hash(state),
+            DataNode::Duration(d) => d.hash(state),
+            DataNode::Bool(b) => b.hash(state),
+            DataNode::Null => {}
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+
+use rust_decimal::{prelude::ToPrimitive, RoundingStrategy};
+
+use super::{data_node::integer_from_float, DataNode, ValueType};
+use crate::{error::FilsonResult, FilsonError};
+
+/// Built-in functions, that transform the value of their argument.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Function {
+    /// Number of elements in a collection or characters in a string.
+    Len,
+    /// Number of bytes in a string.
+    ByteLen,
+    Lower,
+    Upper,
+    Trim,
+    Abs,
+    /// Rounds half away from zero.
+    Round,
+    Floor,
+    Ceil,
+    /// Truncates towards zero.
+    ToInt,
+    ToFloat,
+    ToString,
+    /// Keys of a map as a set.
+    Keys,
+    /// Values of a map as an array.
+    Values,
+}
+
+impl<T: AsRef<str>> From<T> for Function {
+    fn from(value: T) -> Self {
+        match value.as_ref() {
+            "len" => Self::Len,
+            "byte_len" => Self::ByteLen,
+            "lower" => Self::Lower,
+            "upper" => Self::Upper,
+            "trim" => Self::Trim,
+            "abs" => Self::Abs,
+            "round" => Self::Round,
+            "floor" => Self::Floor,
+            "ceil" => Self::Ceil,
+            "to_int" => Self::ToInt,
+            "to_float" => Self::ToFloat,
+            "to_string" => Self::ToString,
+            "keys" => Self::Keys,
+            "values" => Self::Values,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Function {
+    /// Types of the argument the function can be applied to.
+    pub fn accepts(&self) -> &'static [ValueType] {
+        match self {
+            Function::Len => &[ValueType::String, ValueType::Collection],
+            Function::ByteLen | Function::Lower | Function::Upper | Function::Trim => {
+                &[ValueType::String]
+            }
+            Function::Abs | Function::Round | Function::Floor | Function::Ceil => {
+                &[ValueType::Number]
+            }
+            Function::ToInt | Function::ToFloat => &[ValueType::Number, ValueType::String],
+            Function::ToString => &[
+                ValueType::Number,
+                ValueType::String,
+                ValueType::DateTime,
+                ValueType::Duration,
+                ValueType::Bool,
+                ValueType::Null,
+            ],
+            Function::Keys | Function::Values => &[ValueType::Map],
+        }
+    }
+
+    /// Type of the result, `arg` is the type of the argument, if it is known before the filter is applied.
+    pub fn output_type(&self, arg: Option<ValueType>) -> ValueType {
+        match self {
+            Function::Len | Function::ByteLen | Function::ToInt => ValueType::Int,
+            Function::Lower | Function::Upper | Function::Trim | Function::ToString => {
+                ValueType::String
+            }
+            Function::Abs | Function::Round | Function::Floor | Function::Ceil => {
+                arg.unwrap_or(ValueType::Number)
+            }
+            Function::ToFloat => ValueType::Float,
+            Function::Keys => ValueType::Set,
+            Function::Values => ValueType::Array,
+        }
+    }
+
+    pub fn apply<'a>(&self, node: &DataNode<'a>) -> FilsonResult<DataNode<'a>> {
+        if !self.accepts().iter().any(|t| t.is_type_of(node)) {
+            return Err(FilsonError::TypeError);
+        }
+        let res = match (self, node) {
+            (Function::Len, DataNode::Str(s)) => DataNode::I64(s.chars().count() as i64),
+            (Function::Len, DataNode::Array(arr)) => DataNode::I64(arr.len() as i64),
+            (Function::Len, DataNode::Set(set)) => DataNode::I64(set.len() as i64),
+            (Function::Len, DataNode::Map(map)) => DataNode::I64(map.len() as i64),
+            (Function::ByteLen, DataNode::Str(s)) => DataNode::I64(s.len() as i64),
+            (Function::Lower, DataNode::Str(s)) => s.to_lowercase().into(),
+            (Function::Upper, DataNode::Str(s)) => s.to_uppercase().into(),
+            (Function::Trim, DataNode::Str(Cow::Borrowed(s))) => s.trim().into(),
+            (Function::Trim, DataNode::Str(s)) => s.trim().to_owned().into(),
+            (Function::Abs, DataNode::F64(f)) => f.abs().into(),
+            (Function::Abs, DataNode::Decimal(d)) => d.abs().into(),
+            (Function::Abs, _) if node < &DataNode::I64(0) => node.negate()?,
+            (Function::Round, DataNode::F64(f)) => f.round().into(),
+            (Function::Round, DataNode::Decimal(d)) => d
+                .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+                .into(),
+            (Function::Floor, DataNode::F64(f)) => f.floor().into(),
+            (Function::Floor, DataNode::Decimal(d)) => d.floor().into(),
+            (Function::Ceil, DataNode::F64(f)) => f.ceil().into(),
+            (Function::Ceil, DataNode::Decimal(d)) => d.ceil().into(),
+            (Function::ToInt, DataNode::F64(f)) => {
+                integer_from_float(f.trunc()).ok_or(FilsonError::ConversionError)?
+            }
+            // can't fail, decimals fit into 96 bits
+            (Function::ToInt, DataNode::Decimal(d)) => d.trunc().to_i128().unwrap().into(),
+            (Function::ToInt, DataNode::Str(s)) => parse_int(s.trim())?,
+            (Function::ToFloat, DataNode::Str(s)) => match s.trim().parse::<f64>() {
+                Ok(f) if f.is_finite() => f.into(),
+                _ => return Err(FilsonError::ConversionError),
+            },
+            // can't fail, decimals fit into a float
+            (Function::ToFloat, DataNode::Decimal(d)) => d.to_f64().unwrap().into(),
+            (Function::ToFloat, DataNode::I64(i)) => (*i as f64).into(),
+            (Function::ToFloat, DataNode::U64(u)) => (*u as f64).into(),
+            (Function::ToFloat, DataNode::I128(i)) => (*i as f64).into(),
+            (Function::ToFloat, DataNode::U128(u)) => (*u as f64).into(),
+            (Function::ToString, node) => to_string(node).into(),
+            (Function::Keys, DataNode::Map(map)) => DataNode::Set(map.keys().cloned().collect()),
+            (Function::Values, DataNode::Map(map)) => {
+                DataNode::Array(map.values().cloned().collect())
+            }
+            // the rest are already of the requested type or stay the same
+            _ => node.clone(),
+        };
+        Ok(res)
+    }
+}
+
+fn parse_int(s: &str) -> FilsonResult<DataNode<'static>> {
+    if let Ok(i) = s.parse::<i128>() {
+        return Ok(i.into());
+    }
+    s.parse::<u128>()
+        .map(DataNode::from)
+        .map_err(|_| FilsonError::ConversionError)
+}
+
+/// Expects anything but a collection.
+fn to_string(node: &DataNode) -> String {
+    match node {
+        DataNode::I64(i) => i.to_string(),
+        DataNode::U64(u) => u.to_string(),
+        DataNode::I128(i) => i.to_string(),
+        DataNode::U128(u) => u.to_string(),
+        DataNode::F64(f) => f.to_string(),
+        DataNode::Decimal(d) => d.to_string(),
+        DataNode::Str(s) => s.to_string(),
+        DataNode::DateTime(dt) => dt.to_rfc3339(),
+        DataNode::Duration(d) => d.to_string(),
+        DataNode::Bool(b) => b.to_string(),
+        DataNode::Null => "null".to_owned(),
+        DataNode::Map(_) | DataNode::Set(_) | DataNode::Array(_) => unreachable!(),
+    }
+}
@@ -1,5 +1,15 @@
+mod arithmetic_op;
 mod data_node;
+mod function;
 mod op;
+mod params;
+mod pattern;
+mod value_type;
 
+pub(crate) use arithmetic_op::ArithmeticOp;
 pub use data_node::DataNode;
+pub(crate) use function::Function;
 pub(crate) use op::Op;
+pub use params::Params;
+pub(crate) use pattern::{Pattern, FLAGS as PATTERN_FLAGS};
+pub(crate) use value_type::ValueType;
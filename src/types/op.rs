@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub(crate) enum Op {
     Eq,
@@ -23,12 +25,20 @@ impl<T: AsRef<str>> From<T> for Op {
 }
 
 impl Op {
+    /// Whether the operation holds for operands ordered as `ordering`.
+    pub fn is_satisfied_by(&self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Gt => ordering.is_gt(),
+            Op::Lt => ordering.is_lt(),
+            Op::Gte => ordering.is_ge(),
+            Op::Lte => ordering.is_le(),
+        }
+    }
+
     #[cfg(not(feature = "collection_ordering"))]
     pub fn is_ordering(&self) -> bool {
-        if let Op::Eq | Op::Ne = self {
-            false
-        } else {
-            true
-        }
+        !matches!(self, Op::Eq | Op::Ne)
     }
 }
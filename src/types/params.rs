@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use crate::{DataNode, FilsonError, FilsonResult};
+
+/// Values of the `$name` parameters in a condition, bound when the filter is applied.
+///
+/// Parameters keep the values out of the condition, so the values that come from users can't change its meaning,
+/// and the same compiled filter can be applied with different values.
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")] {
+/// use serde_json::json;
+/// use filson::{Appliable, Filter, FilsonError, Params};
+///
+/// let flt = Filter::new(r#"compare("/tenant" == $tenant) && is_contained("/role" $roles)"#).unwrap();
+/// assert_eq!(flt.params(), ["roles", "tenant"]);
+///
+/// let doc = json!({"tenant": "acme", "role": "admin"});
+/// let params = Params::new()
+///     .bind("tenant", "acme")
+///     .bind("roles", vec!["admin".into(), "owner".into()]);
+/// assert_eq!(flt.apply_with(&doc, &params), Ok(true));
+/// // a value that looks like a part of the condition is just a string
+/// let params = params.bind("tenant", r#"acme") || compare("/tenant" != 1"#);
+/// assert_eq!(flt.apply_with(&doc, &params), Ok(false));
+///
+/// // every parameter has to be bound, even if the condition could be decided without it
+/// assert_eq!(
+///     flt.apply_with(&doc, &Params::new().bind("tenant", "other")),
+///     Err(FilsonError::UnboundParameterError("roles".into()))
+/// );
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Params<'a> {
+    values: BTreeMap<String, DataNode<'a>>,
+}
+
+impl<'a> Params<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the parameter `name`, written as `$name` in the condition, to the `value`, replacing the previous one.
+    pub fn bind(mut self, name: impl Into<String>, value: impl Into<DataNode<'a>>) -> Self {
+        self.values.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DataNode<'a>> {
+        self.values.get(name)
+    }
+
+    /// Fails with the first of the `names`, that isn't bound.
+    pub(crate) fn check_bound<'n>(
+        &self,
+        names: impl IntoIterator<Item = &'n str>,
+    ) -> FilsonResult<()> {
+        match names
+            .into_iter()
+            .find(|name| !self.values.contains_key(*name))
+        {
+            Some(name) => Err(FilsonError::UnboundParameterError(name.to_owned())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a, K: Into<String>, V: Into<DataNode<'a>>> FromIterator<(K, V)> for Params<'a> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), |params, (name, value)| {
+            params.bind(name, value)
+        })
+    }
+}
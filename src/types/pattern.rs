@@ -0,0 +1,47 @@
+use regex::{Regex, RegexBuilder};
+
+/// Upper bound of the memory a single compiled pattern may take, keeps huge repetitions like `a{100000}` in check.
+const SIZE_LIMIT: usize = 1 << 20;
+
+/// Flags that may follow the pattern in the `matches` actor.
+pub(crate) const FLAGS: &str = "imsxU";
+
+/// Regular expression compiled once, when the filter is built.
+///
+/// The underlying engine guarantees matching in linear time, so there is no catastrophic backtracking.
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    regex: Regex,
+    /// Normalized to the order of [FLAGS], without duplicates, so the same flags in any order are equal.
+    flags: String,
+}
+
+impl Pattern {
+    /// `flags` are expected to be validated against [FLAGS] beforehand.
+    pub(crate) fn new(pattern: &str, flags: &str) -> Result<Self, regex::Error> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(flags.contains('i'))
+            .multi_line(flags.contains('m'))
+            .dot_matches_new_line(flags.contains('s'))
+            .ignore_whitespace(flags.contains('x'))
+            .swap_greed(flags.contains('U'))
+            .size_limit(SIZE_LIMIT)
+            .dfa_size_limit(SIZE_LIMIT)
+            .build()?;
+        Ok(Self {
+            regex,
+            flags: FLAGS.chars().filter(|flag| flags.contains(*flag)).collect(),
+        })
+    }
+
+    #[inline]
+    pub(crate) fn is_match(&self, haystack: &str) -> bool {
+        self.regex.is_match(haystack)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str() && self.flags == other.flags
+    }
+}
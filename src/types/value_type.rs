@@ -0,0 +1,119 @@
+use super::DataNode;
+
+/// Type names accepted by the `is_type` actor, also used to check the types of the functions when the filter is built.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum ValueType {
+    /// Integers of any width.
+    Int,
+    Float,
+    Decimal,
+    /// Any of [ValueType::Int], [ValueType::Float] and [ValueType::Decimal].
+    Number,
+    String,
+    DateTime,
+    Duration,
+    Bool,
+    Null,
+    Array,
+    Set,
+    Map,
+    /// Any of [ValueType::Array], [ValueType::Set] and [ValueType::Map].
+    Collection,
+}
+
+impl<T: AsRef<str>> From<T> for ValueType {
+    fn from(value: T) -> Self {
+        match value.as_ref() {
+            "int" => Self::Int,
+            "float" => Self::Float,
+            "decimal" => Self::Decimal,
+            "number" => Self::Number,
+            "string" => Self::String,
+            "datetime" => Self::DateTime,
+            "duration" => Self::Duration,
+            "bool" => Self::Bool,
+            "null" => Self::Null,
+            "array" => Self::Array,
+            "set" => Self::Set,
+            "map" => Self::Map,
+            "collection" => Self::Collection,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl ValueType {
+    pub fn of(node: &DataNode) -> Self {
+        match node {
+            DataNode::Map(_) => ValueType::Map,
+            DataNode::Set(_) => ValueType::Set,
+            DataNode::Array(_) => ValueType::Array,
+            DataNode::I64(_) | DataNode::U64(_) | DataNode::I128(_) | DataNode::U128(_) => {
+                ValueType::Int
+            }
+            DataNode::F64(_) => ValueType::Float,
+            DataNode::Decimal(_) => ValueType::Decimal,
+            DataNode::Str(_) => ValueType::String,
+            DataNode::DateTime(_) => ValueType::DateTime,
+            DataNode::Duration(_) => ValueType::Duration,
+            DataNode::Bool(_) => ValueType::Bool,
+            DataNode::Null => ValueType::Null,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::Decimal => "decimal",
+            ValueType::Number => "number",
+            ValueType::String => "string",
+            ValueType::DateTime => "datetime",
+            ValueType::Duration => "duration",
+            ValueType::Bool => "bool",
+            ValueType::Null => "null",
+            ValueType::Array => "array",
+            ValueType::Set => "set",
+            ValueType::Map => "map",
+            ValueType::Collection => "collection",
+        }
+    }
+
+    /// Whether there is a value of both of the types, e.g. `number` overlaps `int`.
+    pub fn overlaps(&self, other: &ValueType) -> bool {
+        self.members()
+            .iter()
+            .any(|member| other.members().contains(member))
+    }
+
+    fn members(&self) -> &[ValueType] {
+        match self {
+            ValueType::Number => &[ValueType::Int, ValueType::Float, ValueType::Decimal],
+            ValueType::Collection => &[ValueType::Array, ValueType::Set, ValueType::Map],
+            _ => std::slice::from_ref(self),
+        }
+    }
+
+    pub fn is_type_of(&self, node: &DataNode) -> bool {
+        match self {
+            ValueType::Int => matches!(
+                node,
+                DataNode::I64(_) | DataNode::U64(_) | DataNode::I128(_) | DataNode::U128(_)
+            ),
+            ValueType::Float => matches!(node, DataNode::F64(_)),
+            ValueType::Decimal => matches!(node, DataNode::Decimal(_)),
+            ValueType::Number => [ValueType::Int, ValueType::Float, ValueType::Decimal]
+                .iter()
+                .any(|t| t.is_type_of(node)),
+            ValueType::String => matches!(node, DataNode::Str(_)),
+            ValueType::DateTime => matches!(node, DataNode::DateTime(_)),
+            ValueType::Duration => matches!(node, DataNode::Duration(_)),
+            ValueType::Bool => matches!(node, DataNode::Bool(_)),
+            ValueType::Null => matches!(node, DataNode::Null),
+            ValueType::Array => matches!(node, DataNode::Array(_)),
+            ValueType::Set => matches!(node, DataNode::Set(_)),
+            ValueType::Map => matches!(node, DataNode::Map(_)),
+            ValueType::Collection => node.is_collection_type(),
+        }
+    }
+}
@@ -55,6 +55,8 @@ mod test_compare {
             ("map", r#"<"first": 1, "second": 2>"#),
             ("set", "{1, 2}"),
             ("array", "[1, 2]"),
+            ("datetime", r#"t"2024-01-01T00:00:00Z""#),
+            ("duration", "1h30m"),
         ];
         for doublet in doublets {
             common::run_doublet_test(
@@ -70,6 +72,75 @@ mod test_compare {
         );
     }
 
+    #[test]
+    fn compare_with_owned_extracted_string() {
+        common::run_doublet_test(
+            ("text_uppercase", r#""KARL""#),
+            vec![Ok(false), Ok(true)],
+            construct_compare_eq_from_doublet,
+        );
+    }
+
+    #[test]
+    fn compare_datetimes_by_instant() {
+        let conditions = [
+            (
+                r#"compare("datetime" == t"2024-01-01T03:00:00+03:00")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare("datetime" > t"2024-06-01T11:00:00+02:00")"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare("datetime" <= t"2024-06-01T10:00:00Z")"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"compare("datetime" < t"2024-06-01T10:00:00Z")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn compare_with_radix_integers() {
+        let conditions = [
+            (r#"compare("int" == 0x1)"#, vec![Ok(true), Ok(false)]),
+            (r#"compare("int" == 0o2)"#, vec![Ok(false), Ok(true)]),
+            (r#"compare("int" < 0b10)"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"is_contained("int" [0x01, 0b1_0])"#,
+                vec![Ok(true), Ok(true)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn compare_durations() {
+        let conditions = [
+            (
+                r#"compare("duration" == PT1H30M)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (r#"compare("duration" > 1d)"#, vec![Ok(false), Ok(true)]),
+            (r#"compare("duration" <= 1w)"#, vec![Ok(true), Ok(true)]),
+            (
+                r#"is_contained("duration" [5400s, P1W])"#,
+                vec![Ok(true), Ok(true)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
     #[test]
     fn compare_with_all_possible_rhs_types_wrong_lhs() {
         let doublets = [
@@ -81,6 +152,10 @@ mod test_compare {
             ("set", "[1, 2]"),
             ("array", r#"<"first": 1, "second": 2>"#),
             ("null", "true"),
+            ("datetime", r#""2024-01-01T00:00:00Z""#),
+            ("text", r#"t"2024-01-01T00:00:00Z""#),
+            ("duration", "5400"),
+            ("int", "1s"),
         ];
         for doublet in doublets {
             common::run_doublet_test(
@@ -93,140 +168,686 @@ mod test_compare {
 }
 
 #[cfg(test)]
-mod test_intersects {
+mod test_length {
     use super::*;
-    use filson::{get_filter, FilsonError};
+    use filson::FilsonError;
 
-    fn construct_intersects_from_doublet(doublet: (&str, &str)) -> String {
-        format!(r#"intersects("{}" {})"#, doublet.0, doublet.1)
+    #[test]
+    fn length_of_collections_and_strings() {
+        let conditions = [
+            (r#"compare(len("array") == 2)"#, vec![Ok(true), Ok(true)]),
+            (r#"compare(len("set") > 2)"#, vec![Ok(false), Ok(false)]),
+            (r#"compare(len("map") <= 2)"#, vec![Ok(true), Ok(true)]),
+            (r#"compare(len("text") == 4)"#, vec![Ok(false), Ok(true)]),
+            (r#"between(len("text") (4, 10))"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare(len("int") == 1)"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"compare(len("text") == 4.0)"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"compare(byte_len("array") == 2)"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
     }
 
+    #[cfg(feature = "serde_json")]
     #[test]
-    fn intersects_with_correct_types() {
-        let doublets = [
-            ("map", r#"<"third": 3>"#),
-            ("set", "{3}"),
-            ("array", "[3]"),
-            ("text", r#""karl""#),
+    fn length_of_strings_in_chars_or_bytes() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({"name": "Zo\u{eb} \u{1F980}"});
+        let conditions = [
+            (r#"compare(len("/name") == 5)"#, Ok(true)),
+            (r#"compare(byte_len("/name") == 9)"#, Ok(true)),
+            (
+                r#"compare(len("/missing") == 0)"#,
+                Err(FilsonError::ExtractionError),
+            ),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![Ok(false), Ok(true)],
-                construct_intersects_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
     }
+}
+
+#[cfg(test)]
+mod test_functions {
+    use super::*;
+    use filson::{FilsonError, Filter};
 
     #[test]
-    fn intersects_with_unmatching_types() {
-        let doublets = [
-            ("set", r#"<"third": 3>"#),
-            ("map", "{3}"),
-            ("text", "[3]"),
-            ("array", r#""2""#),
-        ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
+    fn string_functions() {
+        let conditions = [
+            (
+                r#"compare(lower("text_uppercase") == "karl")"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare(upper("text") == "KARL")"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"starts_with(upper("text") "TEST")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"matches(lower("text_uppercase") "^k")"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare(len(trim(to_string("int"))) == 1)"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"compare(lower("int") == "a")"#,
                 vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
-                construct_intersects_from_doublet,
-            );
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
         }
     }
 
     #[test]
-    fn intersects_with_illegal_lhs_types() {
-        let doublets = [
-            ("int", r#"<"third": 3>"#),
-            ("float", "{3}"),
-            ("boolean", "[3]"),
-            ("null", r#""2""#),
+    fn numeric_functions() {
+        let conditions = [
+            (r#"compare(abs(-"int") == 1)"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare(abs("float" - 3.0) == 1.0)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare(round("float" / 4) == 1.0)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare(floor("float" * 0.75) == 1.0)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare(ceil("float" * 0.75) == 1.0)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(round("int" + 0.5d) == 2.0d)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (r#"compare(floor("int") == 1)"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare(abs("text") == 1)"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![
-                    Err(FilsonError::IntersectsError),
-                    Err(FilsonError::IntersectsError),
-                ],
-                construct_intersects_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
         }
     }
 
     #[test]
-    fn intersects_with_illegal_rhs_types() {
-        // shouldn't be able to parse
-        // parser should not allow for incorrect types
-        let doublets = [
-            ("int", "1"),
-            ("float", "1.0"),
-            ("boolean", "true"),
-            ("null", "null"),
+    fn conversion_functions() {
+        let conditions = [
+            (
+                r#"compare(to_int("float" * 1.5) == 1)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(to_int(-"float" * 1.5) == -1)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(to_float("int") == 1.0)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(to_string("int") == "1")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(to_string("boolean") == "true")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(to_string("datetime") == "2024-01-01T00:00:00+00:00")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(to_int("text") == 1)"#,
+                vec![
+                    Err(FilsonError::ConversionError),
+                    Err(FilsonError::ConversionError),
+                ],
+            ),
+            (
+                r#"compare(to_string("array") == "[1, 2]")"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
         ];
-        for doublet in doublets {
-            assert!(get_filter(&construct_intersects_from_doublet(doublet)).is_err());
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
         }
     }
-}
-
-#[cfg(test)]
-mod test_is_contained {
-    use super::*;
-    use filson::get_filter;
 
-    fn construct_is_contained_from_doublet(doublet: (&str, &str)) -> String {
-        format!(r#"is_contained("{}" {})"#, doublet.0, doublet.1)
+    #[test]
+    fn map_functions() {
+        let conditions = [
+            (
+                r#"intersects(keys("map") {"first", "team"})"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(keys("map") == {"second", "third"})"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"is_subset(values("map") [1, 2])"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare(len(values("map")) == 2)"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"intersects(keys("array") {1})"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
     }
 
     #[test]
-    fn is_contained_correct_rhs() {
-        let lhsses = ["int", "float", "text", "boolean"];
-        let rhsses = [
-            r#"<1: null, 1.0: null, "test text": null, true: null>"#,
-            r#"{1, 1.0, "test text" , true, <"first": 1, "second": 2>, {1, 2}, [1, 2]}"#,
-            r#"[1, 1.0, "test text" , true, <"first": 1, "second": 2>, {1, 2}, [1, 2]]"#,
+    fn types_are_checked_when_the_filter_is_built() {
+        let conditions = [
+            r#"compare(lower(len("text")) == "a")"#,
+            r#"compare(abs(to_string("int")) == 1)"#,
+            r#"compare(to_int("int") + lower("text") == 1)"#,
+            r#"intersects(to_float("int") [1.0])"#,
+            r#"ends_with(keys("map") "a")"#,
         ];
-        for lhs in lhsses {
-            for rhs in rhsses {
-                common::run_doublet_test(
-                    (lhs, rhs),
-                    vec![Ok(true), Ok(false)],
-                    construct_is_contained_from_doublet,
-                );
-            }
-        }
-        let null_rhses = ["<null: null>", "{null}", "[null]"];
-        for null_rhs in null_rhses {
-            common::run_doublet_test(
-                ("null", null_rhs),
-                vec![Ok(true), Ok(true)],
-                construct_is_contained_from_doublet,
+        for cond in conditions {
+            assert!(
+                matches!(Filter::new(cond), Err(FilsonError::ParseError(_))),
+                "{cond}"
             );
         }
     }
 
+    #[cfg(feature = "serde_json")]
     #[test]
-    fn is_contained_illegal_rhs() {
-        // shouldn't be able to parse
-        // parser should not allow for incorrect types
-        let rhsses = ["1", "1.0", r#""some text""#, "true", "null"];
-        for rhs in rhsses {
-            assert!(get_filter(&construct_is_contained_from_doublet(("whatever", rhs))).is_err());
+    fn normalize_values_of_a_document() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({
+            "email": "  Karl@Example.COM ",
+            "qty": " 42 ",
+            "price": "4.20",
+            "labels": {"team": "core", "tier": 1},
+        });
+        let conditions = [
+            (
+                r#"compare(lower(trim("/email")) == "karl@example.com")"#,
+                Ok(true),
+            ),
+            (r#"compare(to_int("/qty") > 40)"#, Ok(true)),
+            (r#"compare(to_float("/price") * 10.0 == 42.0)"#, Ok(true)),
+            (r#"intersects(keys("/labels") {"team", "env"})"#, Ok(true)),
+            (
+                r#"compare(to_int("/price") == 4)"#,
+                Err(FilsonError::ConversionError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
     }
 }
 
 #[cfg(test)]
-mod test_exists {
-    use filson::{get_filter, Appliable};
-
+mod test_arithmetic {
     use super::*;
-
-    fn construct_exists(path: &str) -> String {
-        format!(r#"exists("{}")"#, path)
-    }
+    use filson::FilsonError;
+
+    #[test]
+    fn arithmetic_on_values_by_paths() {
+        let conditions = [
+            (
+                r#"compare("int" + "int" - 2 * "int" == 0)"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"compare("int" * len("array") - 1 == 3)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare(("int" + 1) * 2 == 6)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (r#"compare(-"int" < 0)"#, vec![Ok(true), Ok(true)]),
+            (r#"compare("float" / 2 == 0.5)"#, vec![Ok(true), Ok(false)]),
+            (r#"between("int" * 10 [15, 25])"#, vec![Ok(false), Ok(true)]),
+            (
+                r#"compare("int" + "text" == 1)"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"compare("int" + "missing" == 1)"#,
+                vec![
+                    Err(FilsonError::ExtractionError),
+                    Err(FilsonError::ExtractionError),
+                ],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn numeric_types_are_promoted() {
+        let conditions = [
+            // integers stay integers
+            (r#"compare("int" / 2 == 0)"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare("int" / 2 == 0.5)"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (r#"compare(-7 / "int" == -7)"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare(-7 % ("int" + 3) == -3)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            // a float makes a float
+            (r#"compare("int" / 2.0 == 0.5)"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare("float" + "int" == 2.0)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            // otherwise a decimal makes a decimal
+            (
+                r#"compare("int" / 3.0d == 0.3333333333333333333333333333d)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare("int" + 0.5d == 1.5d)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare("float" + 0.5d == 1.5)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn division_by_zero_and_overflow() {
+        let conditions = [
+            (
+                r#"compare("int" / ("int" - "int") == 1)"#,
+                vec![
+                    Err(FilsonError::DivisionByZeroError),
+                    Err(FilsonError::DivisionByZeroError),
+                ],
+            ),
+            (
+                r#"compare("int" % 0 == 1)"#,
+                vec![
+                    Err(FilsonError::DivisionByZeroError),
+                    Err(FilsonError::DivisionByZeroError),
+                ],
+            ),
+            (
+                r#"compare("float" / 0.0 == 1.0)"#,
+                vec![
+                    Err(FilsonError::DivisionByZeroError),
+                    Err(FilsonError::DivisionByZeroError),
+                ],
+            ),
+            (
+                r#"compare("int" + 340282366920938463463374607431768211454 > 0)"#,
+                vec![Ok(true), Err(FilsonError::OverflowError)],
+            ),
+            (
+                r#"compare(-"int" - 170141183460469231731687303715884105727 < 0)"#,
+                vec![Ok(true), Err(FilsonError::OverflowError)],
+            ),
+            (
+                r#"compare("float" * 1.0e308 * 10.0 > 0.0)"#,
+                vec![
+                    Err(FilsonError::OverflowError),
+                    Err(FilsonError::OverflowError),
+                ],
+            ),
+            (
+                r#"compare("int" * 1.0d * 79228162514264337593543950335 > 0.0d)"#,
+                vec![Ok(true), Err(FilsonError::OverflowError)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn integers_of_any_width_are_exact() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({"max": u64::MAX, "min": i64::MIN, "qty": 3, "price": 400});
+        let conditions = [
+            (r#"compare("/price" * "/qty" > 1000)"#, Ok(true)),
+            (r#"compare("/max" + 1 == 18446744073709551616)"#, Ok(true)),
+            (
+                r#"compare("/min" - "/max" == -27670116110564327423)"#,
+                Ok(true),
+            ),
+            (r#"compare("/max" * "/max" > @"/max")"#, Ok(true)),
+            (r#"compare(-"/min" == 9223372036854775808)"#, Ok(true)),
+            (
+                r#"compare("/max" * "/max" * "/max" > 0)"#,
+                Err(FilsonError::OverflowError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_between {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn between_inclusive_and_exclusive_bounds() {
+        let conditions = [
+            (r#"between("int" 1 2)"#, vec![Ok(true), Ok(true)]),
+            (r#"between("int" [1, 2])"#, vec![Ok(true), Ok(true)]),
+            (r#"between("int" (1, 2])"#, vec![Ok(false), Ok(true)]),
+            (r#"between("int" [1, 2))"#, vec![Ok(true), Ok(false)]),
+            (r#"between("int" (1, 2))"#, vec![Ok(false), Ok(false)]),
+            (r#"between("int" [2, 1])"#, vec![Ok(false), Ok(false)]),
+            (r#"between("float" [0.5, 1.5))"#, vec![Ok(true), Ok(false)]),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn between_works_for_all_ordered_types() {
+        let conditions = [
+            (r#"between("text" ["a", "l"))"#, vec![Ok(false), Ok(true)]),
+            (
+                r#"between("datetime" [t"2024-01-01T00:00:00Z", t"2024-06-01T00:00:00Z"))"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"between("datetime" "2023-12-31T00:00:00Z" "2024-01-02T00:00:00Z")"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (r#"between("duration" (1h, 1d])"#, vec![Ok(true), Ok(false)]),
+            (r#"between("boolean" false true)"#, vec![Ok(true), Ok(true)]),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn between_checks_types_of_both_bounds() {
+        let conditions = [
+            (
+                r#"between("int" [5, "z"])"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"between("int" [1.0, 2.0])"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"between("missing" [1, 2])"#,
+                vec![
+                    Err(FilsonError::ExtractionError),
+                    Err(FilsonError::ExtractionError),
+                ],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_references {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn compare_with_values_of_the_same_data() {
+        let conditions = [
+            (r#"compare("int" == @"int")"#, vec![Ok(true), Ok(true)]),
+            (
+                r#"compare(len("array") == @"int")"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (r#"between("int" [@"int", 2))"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"compare("int" == @"float")"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"compare("int" == @"missing")"#,
+                vec![
+                    Err(FilsonError::ExtractionError),
+                    Err(FilsonError::ExtractionError),
+                ],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn collection_actors_with_values_of_the_same_data() {
+        let conditions = [
+            (r#"is_contained("int" @"array")"#, vec![Ok(true), Ok(true)]),
+            (r#"is_contained("int" @"map")"#, vec![Ok(false), Ok(false)]),
+            (r#"intersects("array" @"array")"#, vec![Ok(true), Ok(true)]),
+            (r#"is_subset("set" @"set")"#, vec![Ok(true), Ok(true)]),
+            (r#"is_superset("text" @"text")"#, vec![Ok(true), Ok(true)]),
+            (
+                r#"is_contained("int" @"int")"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"intersects("array" @"set")"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn compare_fields_of_a_document() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+            "owner": "karl",
+            "members": ["karl", "fritz"]
+        });
+        let conditions = [
+            (r#"compare("/updated_at" >= @"/created_at")"#, Ok(true)),
+            (r#"is_contained("/owner" @"/members")"#, Ok(true)),
+            (
+                r#"is_contained("/owner" @"/members") && compare("/owner" != @"/members/1")"#,
+                Ok(true),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_intersects {
+    use super::*;
+    use filson::{get_filter, FilsonError};
+
+    fn construct_intersects_from_doublet(doublet: (&str, &str)) -> String {
+        format!(r#"intersects("{}" {})"#, doublet.0, doublet.1)
+    }
+
+    #[test]
+    fn intersects_with_correct_types() {
+        let doublets = [
+            ("map", r#"<"third": 3>"#),
+            ("set", "{3}"),
+            ("array", "[3]"),
+            ("text", r#""karl""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![Ok(false), Ok(true)],
+                construct_intersects_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn intersects_with_unmatching_types() {
+        let doublets = [
+            ("set", r#"<"third": 3>"#),
+            ("map", "{3}"),
+            ("text", "[3]"),
+            ("array", r#""2""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+                construct_intersects_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn intersects_with_illegal_lhs_types() {
+        let doublets = [
+            ("int", r#"<"third": 3>"#),
+            ("float", "{3}"),
+            ("boolean", "[3]"),
+            ("null", r#""2""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![
+                    Err(FilsonError::IntersectsError),
+                    Err(FilsonError::IntersectsError),
+                ],
+                construct_intersects_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn intersects_with_illegal_rhs_types() {
+        // shouldn't be able to parse
+        // parser should not allow for incorrect types
+        let doublets = [
+            ("int", "1"),
+            ("float", "1.0"),
+            ("boolean", "true"),
+            ("null", "null"),
+        ];
+        for doublet in doublets {
+            assert!(get_filter(&construct_intersects_from_doublet(doublet)).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_is_contained {
+    use super::*;
+    use filson::get_filter;
+
+    fn construct_is_contained_from_doublet(doublet: (&str, &str)) -> String {
+        format!(r#"is_contained("{}" {})"#, doublet.0, doublet.1)
+    }
+
+    #[test]
+    fn is_contained_correct_rhs() {
+        let lhsses = ["int", "float", "text", "boolean"];
+        let rhsses = [
+            r#"<1: null, 1.0: null, "test text": null, true: null>"#,
+            r#"{1, 1.0, "test text" , true, <"first": 1, "second": 2>, {1, 2}, [1, 2]}"#,
+            r#"[1, 1.0, "test text" , true, <"first": 1, "second": 2>, {1, 2}, [1, 2]]"#,
+        ];
+        for lhs in lhsses {
+            for rhs in rhsses {
+                common::run_doublet_test(
+                    (lhs, rhs),
+                    vec![Ok(true), Ok(false)],
+                    construct_is_contained_from_doublet,
+                );
+            }
+        }
+        let null_rhses = ["<null: null>", "{null}", "[null]"];
+        for null_rhs in null_rhses {
+            common::run_doublet_test(
+                ("null", null_rhs),
+                vec![Ok(true), Ok(true)],
+                construct_is_contained_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn is_contained_illegal_rhs() {
+        // shouldn't be able to parse
+        // parser should not allow for incorrect types
+        let rhsses = ["1", "1.0", r#""some text""#, "true", "null"];
+        for rhs in rhsses {
+            assert!(get_filter(&construct_is_contained_from_doublet(("whatever", rhs))).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_exists {
+    use filson::{get_filter, Appliable};
+
+    use super::*;
+
+    fn construct_exists(path: &str) -> String {
+        format!(r#"exists("{}")"#, path)
+    }
 
     #[test]
     fn actually_exists() {
@@ -234,279 +855,1818 @@ mod test_exists {
             "int", "float", "text", "boolean", "map", "set", "array", "null",
         ];
         let test_data = setup::get_test_data();
-        for path in existing_paths {
-            let cond = construct_exists(path);
-            let flt = get_filter(&cond).unwrap();
-            let actual = test_data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>();
-            assert_eq!(actual, vec![Ok(true), Ok(true)]);
+        for path in existing_paths {
+            let cond = construct_exists(path);
+            let flt = get_filter(&cond).unwrap();
+            let actual = test_data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>();
+            assert_eq!(actual, vec![Ok(true), Ok(true)]);
+        }
+    }
+
+    #[test]
+    fn doesnt_exist() {
+        let fake_path = "fake";
+        let test_data = setup::get_test_data();
+        let cond = construct_exists(fake_path);
+        let flt = get_filter(&cond).unwrap();
+        let actual = test_data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>();
+        assert_eq!(actual, vec![Ok(false), Ok(false)]);
+    }
+}
+
+#[cfg(test)]
+mod test_is_type {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn matches_the_type_of_the_value() {
+        let conditions = [
+            ("int", "int"),
+            ("float", "float"),
+            ("text", "string"),
+            ("boolean", "bool"),
+            ("null", "null"),
+            ("array", "array"),
+            ("set", "set"),
+            ("map", "map"),
+            ("datetime", "datetime"),
+            ("duration", "duration"),
+        ];
+        for (path, value_type) in conditions {
+            let cond = format!(r#"is_type("{path}" {value_type})"#);
+            common::run_singlet_test(&cond, vec![Ok(true), Ok(true)]);
+            let cond = format!(r#"is_type("{path}" decimal)"#);
+            common::run_singlet_test(&cond, vec![Ok(false), Ok(false)]);
+        }
+    }
+
+    #[test]
+    fn matches_type_groups_and_unions() {
+        let conditions = [
+            (r#"is_type("int" number)"#, vec![Ok(true), Ok(true)]),
+            (r#"is_type("float" number)"#, vec![Ok(true), Ok(true)]),
+            (r#"is_type("text" number)"#, vec![Ok(false), Ok(false)]),
+            (r#"is_type("set" collection)"#, vec![Ok(true), Ok(true)]),
+            (r#"is_type("text" collection)"#, vec![Ok(false), Ok(false)]),
+            (r#"is_type("float" int | null)"#, vec![Ok(false), Ok(false)]),
+            (r#"is_type("null" int | null)"#, vec![Ok(true), Ok(true)]),
+            (
+                r#"is_type("missing" null)"#,
+                vec![
+                    Err(FilsonError::ExtractionError),
+                    Err(FilsonError::ExtractionError),
+                ],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn guards_comparisons_of_inconsistent_data() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let flt = get_filter(r#"is_type("/age" int) && compare("/age" >= 18)"#).unwrap();
+        let data = [json!({"age": 30}), json!({"age": "30"}), json!({"age": 10})];
+        let actual = data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>();
+        assert_eq!(actual, vec![Ok(true), Ok(false), Ok(false)]);
+    }
+}
+
+#[cfg(test)]
+mod test_is_subset {
+    use super::*;
+    use filson::{get_filter, FilsonError};
+
+    fn construct_is_subset_from_doublet(doublet: (&str, &str)) -> String {
+        format!(r#"is_subset("{}" {})"#, doublet.0, doublet.1)
+    }
+
+    #[test]
+    fn is_subset_legal_lhs_rhs() {
+        let doublets = [
+            ("array", "[2, 3, 5]"),
+            ("set", "{2, 3, 5}"),
+            ("map", r#"<"second": 2, "third": 3, "fifth": 5>"#),
+            ("text", r#""karlissimo""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![Ok(false), Ok(true)],
+                construct_is_subset_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn is_subset_illegal_lhs() {
+        let doublets = [
+            ("int", "[1, 2, 5]"),
+            ("float", "{1, 2, 5}"),
+            ("boolean", r#"<"first": 1, "second": 2, "fifth": 5>"#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![
+                    Err(FilsonError::IsSubsetError),
+                    Err(FilsonError::IsSubsetError),
+                ],
+                construct_is_subset_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn is_subset_illegal_rhs() {
+        // shouldn't be able to parse
+        // parser should not allow for incorrect types
+        let rhsses = ["1", "1.0", "true", "null"];
+        for rhs in rhsses {
+            assert!(get_filter(&construct_is_subset_from_doublet(("whatever", rhs))).is_err());
+        }
+    }
+
+    #[test]
+    fn is_subset_unmatching_lhs_rhs() {
+        let doublets = [
+            ("set", "[2, 3, 5]"),
+            ("array", "{2, 3, 5}"),
+            ("text", r#"<"second": 2, "third": 3, "fifth": 5>"#),
+            ("map", r#""karlissimo""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+                construct_is_subset_from_doublet,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_is_superset {
+    use super::*;
+    use filson::{get_filter, FilsonError};
+
+    fn construct_is_superset_from_doublet(doublet: (&str, &str)) -> String {
+        format!(r#"is_superset("{}" {})"#, doublet.0, doublet.1)
+    }
+
+    #[test]
+    fn is_superset_legal_lhs_rhs() {
+        let doublets = [
+            ("array", "[3]"),
+            ("set", "{3}"),
+            ("map", r#"<"third": 3>"#),
+            ("text", r#""ka""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![Ok(false), Ok(true)],
+                construct_is_superset_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn is_superset_illegal_lhs() {
+        let doublets = [
+            ("int", "[1, 2, 5]"),
+            ("float", "{1, 2, 5}"),
+            ("boolean", r#"<"first": 1, "second": 2, "fifth": 5>"#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![
+                    Err(FilsonError::IsSupersetError),
+                    Err(FilsonError::IsSupersetError),
+                ],
+                construct_is_superset_from_doublet,
+            );
+        }
+    }
+
+    #[test]
+    fn is_superset_illegal_rhs() {
+        // shouldn't be able to parse
+        // parser should not allow for incorrect types
+        let rhsses = ["1", "1.0", "true", "null"];
+        for rhs in rhsses {
+            assert!(get_filter(&construct_is_superset_from_doublet(("whatever", rhs))).is_err());
+        }
+    }
+
+    #[test]
+    fn is_superset_unmatching_lhs_rhs() {
+        let doublets = [
+            ("set", "[2, 3, 5]"),
+            ("array", "{2, 3, 5}"),
+            ("text", r#"<"second": 2, "third": 3, "fifth": 5>"#),
+            ("map", r#""karlissimo""#),
+        ];
+        for doublet in doublets {
+            common::run_doublet_test(
+                doublet,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+                construct_is_superset_from_doublet,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_matches {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn matches_strings() {
+        let conditions = [
+            (r#"matches("text" "^test")"#, vec![Ok(true), Ok(false)]),
+            (r#"matches("text" "^TEST")"#, vec![Ok(false), Ok(false)]),
+            (r#"matches("text" "^TEST" "i")"#, vec![Ok(true), Ok(false)]),
+            (r#"matches("text" "^[a-z]+$")"#, vec![Ok(false), Ok(true)]),
+            (r#"!matches("text" "\\s")"#, vec![Ok(false), Ok(true)]),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn matches_illegal_lhs() {
+        for lhs in ["int", "float", "boolean", "null", "map", "set", "array"] {
+            common::run_singlet_test(
+                &format!(r#"matches("{lhs}" ".*")"#),
+                vec![
+                    Err(FilsonError::MatchesError),
+                    Err(FilsonError::MatchesError),
+                ],
+            );
+        }
+    }
+
+    #[test]
+    fn matches_illegal_rhs() {
+        // shouldn't be able to parse
+        let conditions = [
+            r#"matches("text" 1)"#,
+            r#"matches("text" "(")"#,
+            r#"matches("text" "a" "z")"#,
+        ];
+        for cond in conditions {
+            assert!(filson::get_filter(cond).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_string_predicates {
+    use super::*;
+    use filson::{get_filter, FilsonError};
+
+    #[test]
+    fn string_predicates() {
+        let conditions = [
+            (r#"starts_with("text" "test")"#, vec![Ok(true), Ok(false)]),
+            (r#"starts_with("text" "TEST")"#, vec![Ok(false), Ok(false)]),
+            (r#"istarts_with("text" "TEST")"#, vec![Ok(true), Ok(false)]),
+            (r#"ends_with("text" "rl")"#, vec![Ok(false), Ok(true)]),
+            (r#"iends_with("text" "RL")"#, vec![Ok(false), Ok(true)]),
+            (r#"contains_str("text" "t t")"#, vec![Ok(true), Ok(false)]),
+            (r#"icontains_str("text" "T T")"#, vec![Ok(true), Ok(false)]),
+            (r#"contains_str("text" "")"#, vec![Ok(true), Ok(true)]),
+            (r#"!ends_with("text" "text")"#, vec![Ok(false), Ok(true)]),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn string_predicates_illegal_lhs() {
+        let actors = [
+            ("starts_with", FilsonError::StartsWithError),
+            ("iends_with", FilsonError::EndsWithError),
+            ("contains_str", FilsonError::ContainsStrError),
+        ];
+        for (actor, err) in actors {
+            for lhs in ["int", "float", "boolean", "null", "map", "set", "array"] {
+                common::run_singlet_test(
+                    &format!(r#"{actor}("{lhs}" "1")"#),
+                    vec![Err(err.clone()), Err(err.clone())],
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn string_predicates_illegal_rhs() {
+        // shouldn't be able to parse
+        let rhsses = ["1", "1.0", "true", "null", "[]", "{}", "<>"];
+        for rhs in rhsses {
+            assert!(get_filter(&format!(r#"starts_with("text" {rhs})"#)).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_quantifiers {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn quantifiers_over_elements() {
+        let conditions = [
+            (r#"any("array", compare("" > 1))"#, vec![Ok(true), Ok(true)]),
+            (
+                r#"all("array", compare("" > 1))"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"none("array", compare("" == 3))"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (r#"any("set", compare("" == 1))"#, vec![Ok(true), Ok(false)]),
+            (
+                r#"count("array", compare("" >= 2)) == 1"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"count("array", exists("")) > 1 && all("array", is_type("" int))"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"any("text", exists(""))"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"any("missing", exists(""))"#,
+                vec![
+                    Err(FilsonError::ExtractionError),
+                    Err(FilsonError::ExtractionError),
+                ],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn paths_are_relative_to_the_element() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({
+            "items": [
+                {"sku": "a", "price": 150, "qty": 1, "tags": ["sale"]},
+                {"sku": "b", "price": 120, "qty": 3, "tags": []},
+                {"sku": "c", "price": 20, "qty": 5, "tags": ["sale", "new"]},
+            ],
+            "empty": [],
+            "price": 1000,
+        });
+        let conditions = [
+            (
+                r#"any("/items", and(compare("/price" > 100), compare("/qty" > 2)))"#,
+                Ok(true),
+            ),
+            (
+                r#"any("/items", compare("/price" > 100) && compare("/qty" > 3))"#,
+                Ok(false),
+            ),
+            (
+                r#"all("/items", compare("/price" * "/qty" >= 100))"#,
+                Ok(true),
+            ),
+            (r#"none("/items", compare("/price" > 200))"#, Ok(true)),
+            (r#"count("/items", compare("/price" > 100)) >= 2"#, Ok(true)),
+            (r#"count("/items", compare("/price" > 100)) > 2"#, Ok(false)),
+            (
+                r#"any("/items", any("/tags", compare("" == "new")))"#,
+                Ok(true),
+            ),
+            (r#"all("/items", compare("/qty" < @"/price"))"#, Ok(true)),
+            (
+                r#"count("/items", compare(len("/tags") == 0)) == 1"#,
+                Ok(true),
+            ),
+            (r#"any("/empty", exists(""))"#, Ok(false)),
+            (r#"all("/empty", exists("/nothing"))"#, Ok(true)),
+            (r#"none("/empty", exists(""))"#, Ok(true)),
+            (r#"count("/empty", exists("")) == 0"#, Ok(true)),
+            // the element is the root of the relative paths, so the price of the document isn't reachable
+            (r#"all("/items", compare("/price" < 1000))"#, Ok(true)),
+            (
+                r#"all("/items", compare("/missing" < 1000))"#,
+                Err(FilsonError::ExtractionError),
+            ),
+            (
+                r#"any("/items", compare("/sku" == 1))"#,
+                Err(FilsonError::TypeError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn evaluation_stops_once_the_result_is_decided() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({"items": [{"price": 1}, {"price": "a"}]});
+        let conditions = [
+            (r#"any("/items", compare("/price" == 1))"#, Ok(true)),
+            (r#"all("/items", compare("/price" == 2))"#, Ok(false)),
+            (r#"none("/items", compare("/price" == 1))"#, Ok(false)),
+            (
+                r#"count("/items", compare("/price" == 1)) == 1"#,
+                Err(FilsonError::TypeError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test_wildcards {
+    use filson::{get_filter, Appliable, FilsonError};
+    use serde_json::json;
+
+    #[test]
+    fn actors_hold_for_any_of_the_values() {
+        let data = json!({
+            "orders": [
+                {"items": [{"sku": "a", "price": 150, "tags": ["sale", "new"]}, {"sku": "b", "price": 20, "tags": []}]},
+                {"items": [{"sku": "c", "price": 120, "tags": ["sale"], "discount": 10}]},
+                {"items": []},
+            ],
+            "labels": {"team": "core", "tier": "gold"},
+            "budget": 100,
+        });
+        let conditions = [
+            (r#"compare("/orders/0/items/*/price" > 100)"#, Ok(true)),
+            (r#"compare("/orders/0/items/*/price" > 200)"#, Ok(false)),
+            (r#"!compare("/orders/*/items/*/price" > 200)"#, Ok(true)),
+            (r#"between("/orders/*/items/*/price" [10, 30])"#, Ok(true)),
+            (
+                r#"is_contained("/orders/*/items/*/sku" ["c", "z"])"#,
+                Ok(true),
+            ),
+            (r#"intersects("/orders/*/items/*/tags" ["new"])"#, Ok(true)),
+            (r#"starts_with("/labels/*" "go")"#, Ok(true)),
+            (r#"is_type("/labels/*" int)"#, Ok(false)),
+            (r#"compare(len("/orders/*/items/*/tags") == 2)"#, Ok(true)),
+            (
+                r#"compare("/orders/*/items/*/price" - "/budget" == 50)"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("/orders/*/items/*/price" - "/orders/*/items/*/price" == 130)"#,
+                Err(FilsonError::MultipleValuesError),
+            ),
+            // items without a discount are skipped
+            (r#"compare("/orders/*/items/*/discount" == 10)"#, Ok(true)),
+            (r#"exists("/orders/*/items/*/discount")"#, Ok(true)),
+            (r#"exists("/orders/*/items/*/missing")"#, Ok(false)),
+            (
+                r#"compare("/orders/*/items/*/missing" == 10)"#,
+                Err(FilsonError::ExtractionError),
+            ),
+            (
+                r#"compare("/budget" < @"/orders/*/items/*/price")"#,
+                Err(FilsonError::MultipleValuesError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+
+    #[test]
+    fn values_are_checked_until_the_first_match() {
+        let data = json!({"items": [{"price": 1}, {"price": "a"}, {"price": 3}]});
+        let conditions = [
+            (r#"compare("/items/*/price" == 1)"#, Ok(true)),
+            (
+                r#"compare("/items/*/price" == 3)"#,
+                Err(FilsonError::TypeError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+
+    #[test]
+    fn wildcards_in_arithmetic() {
+        let data = json!({"items": [{"p": 1, "q": 5}, {"p": 200, "q": 1}]});
+        let conditions = [
+            (r#"compare("/items/*/p" * 5 == 1000)"#, Ok(true)),
+            (r#"compare(-"/items/*/q" == -5)"#, Ok(true)),
+            // no single item has p * q = 1000, so the values aren't combined
+            (
+                r#"compare("/items/*/p" * "/items/*/q" == 1000)"#,
+                Err(FilsonError::MultipleValuesError),
+            ),
+            (r#"any("/items", compare("/p" * "/q" == 1000))"#, Ok(false)),
+            (r#"any("/items", compare("/p" * "/q" == 200))"#, Ok(true)),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+
+    #[test]
+    fn keys_named_asterisk_are_escaped() {
+        let data = json!({"*": 5, "a": 1, "~2": 7, "x": {"*": 5, "a": 1}});
+        let conditions = [
+            (r#"compare("/*" == 1)"#, Ok(true)),
+            (r#"compare("/~2" == 5)"#, Ok(true)),
+            (r#"compare("/~2" == 1)"#, Ok(false)),
+            (r#"compare("/~02" == 7)"#, Ok(true)),
+            (r#"compare(x."*" == 5)"#, Ok(true)),
+            (r#"compare(x."*" == 1)"#, Ok(false)),
+            (r#"compare(x.* == 1)"#, Ok(true)),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+
+    #[test]
+    fn wildcards_in_quantifiers() {
+        let data = json!({
+            "orders": [
+                {"items": [{"price": 150}, {"price": 20}]},
+                {"items": [{"price": 120}]},
+            ],
+        });
+        let conditions = [
+            (
+                r#"count("/orders/*/items", compare("/price" > 100)) == 2"#,
+                Ok(true),
+            ),
+            (
+                r#"all("/orders/*/items", compare("/price" > 10))"#,
+                Ok(true),
+            ),
+            (
+                r#"all("/orders", compare("/items/*/price" > 100))"#,
+                Ok(true),
+            ),
+            (
+                r#"all("/orders", compare("/items/*/price" < 100))"#,
+                Ok(false),
+            ),
+            (
+                r#"any("/orders/*", exists(""))"#,
+                Err(FilsonError::TypeError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test_json_path {
+    use filson::{get_filter, Appliable, FilsonError};
+    use pest::error::InputLocation;
+    use serde_json::json;
+
+    #[test]
+    fn json_path_in_actor_paths() {
+        let data = json!({
+            "store": {
+                "book": [
+                    {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8},
+                    {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12},
+                    {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8},
+                    {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22},
+                ],
+                "bicycle": {"color": "red", "price": 399},
+                "the key": 1,
+            }
+        });
+        let conditions = [
+            (r#"compare("$.store.book[*].price" < 10)"#, Ok(true)),
+            (r#"compare("$.store.book[*].price" > 100)"#, Ok(false)),
+            (r#"compare("$..price" > 100)"#, Ok(true)),
+            (
+                r#"compare("$..book[-1].author" == "J. R. R. Tolkien")"#,
+                Ok(true),
+            ),
+            (r#"compare("$..book[0,1].title" == "Moby Dick")"#, Ok(false)),
+            (r#"compare("$..book[0, 2].title" == "Moby Dick")"#, Ok(true)),
+            (r#"compare("$.store.book[1:3].price" > 10)"#, Ok(true)),
+            (r#"compare("$.store.book[::2].price" > 10)"#, Ok(false)),
+            (r#"compare("$.store['the key']" == 1)"#, Ok(true)),
+            (r#"starts_with("$.store.*.color" "re")"#, Ok(true)),
+            (r#"exists("$..isbn")"#, Ok(true)),
+            (r#"exists("$..missing")"#, Ok(false)),
+            (
+                r#"compare("/store/bicycle/price" > @"$.store.book[3].price")"#,
+                Ok(true),
+            ),
+            (
+                r#"count("$.store.book", compare("$.price" < 10)) == 2"#,
+                Ok(true),
+            ),
+            (r#"all("$.store.book", exists("/author"))"#, Ok(true)),
+            (
+                r#"compare("$.store.missing" == 1)"#,
+                Err(FilsonError::ExtractionError),
+            ),
+            (
+                r#"compare("/store/bicycle/price" > @"$..price")"#,
+                Err(FilsonError::MultipleValuesError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+
+    #[test]
+    fn invalid_json_path_fails_on_build() {
+        let conditions = [
+            (r#"compare("$.a[" == 1)"#, (13, 13)),
+            (r#"exists("$.a[")"#, (12, 12)),
+            (r#"compare("$.a[?length(@) > 0]" == 1)"#, (14, 23)),
+            // positions within the path are unknown, if it has escape sequences
+            (r#"compare("$.a\u005B" == 1)"#, (9, 18)),
+        ];
+        for (cond, span) in conditions {
+            let Err(FilsonError::ParseError(err)) = get_filter(cond) else {
+                panic!("{cond} should fail to parse");
+            };
+            assert_eq!(err.location, InputLocation::Span(span), "{cond}");
+        }
+    }
+
+    #[test]
+    fn json_path_filter_selectors() {
+        let data = json!({
+            "store": {
+                "book": [
+                    {"category": "reference", "title": "Sayings of the Century", "price": 8.95, "tags": ["classic"]},
+                    {"category": "fiction", "title": "Sword of Honour", "price": 12},
+                    {"category": "fiction", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8, "tags": []},
+                    {"category": "fiction", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99},
+                ],
+                "bicycle": {"color": "red", "price": 399},
+            },
+        });
+        let conditions = [
+            (
+                r#"compare("$.store.book[?(@.price < 10)].title" == "Moby Dick")"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("$.store.book[?@.price < 10].title" == "The Lord of the Rings")"#,
+                Ok(false),
+            ),
+            // integers and floats are compared by their value
+            (
+                r#"compare("$.store.book[?@.price >= 8.95].title" == "Sword of Honour")"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("$.store.book[?@.price >= 8.95].title" == "Moby Dick")"#,
+                Ok(false),
+            ),
+            (
+                r#"compare("$.store.book[?@.isbn].category" == "reference")"#,
+                Ok(false),
+            ),
+            (
+                r#"compare("$.store.book[?!@.isbn].title" == "Sword of Honour")"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("$.store.book[?@.category == 'fiction' && (@.price < 10 || @.price > 20)].isbn" == "0-395-19395-8")"#,
+                Ok(true),
+            ),
+            // neither values of different types nor missing values are equal to anything
+            (
+                r#"compare("$.store.book[?@.price != 'x'].title" == "The Lord of the Rings")"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("$.store.book[?@.isbn != '0-553-21311-3'].title" == "Sword of Honour")"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("$.store.book[?@.isbn != '0-553-21311-3'].title" == "Moby Dick")"#,
+                Ok(false),
+            ),
+            (r#"exists("$.store.book[?@.price == '8']")"#, Ok(false)),
+            (
+                r#"exists("$.store.book[?@.tags[-1] == 'classic']")"#,
+                Ok(true),
+            ),
+            // filters select the children of objects as well
+            (r#"compare("$.store[?@.color].price" == 399)"#, Ok(true)),
+            (r#"compare("$..[?@ == 'red']" == "red")"#, Ok(true)),
+            (
+                r#"compare("$.store.book[?@.price > 100].title" == "Moby Dick")"#,
+                Err(FilsonError::ExtractionError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_native_paths {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn native_paths_in_actors() {
+        let conditions = [
+            ("compare(int == 1)", vec![Ok(true), Ok(false)]),
+            ("compare(int * 2 - 1 == @int)", vec![Ok(true), Ok(false)]),
+            (
+                r#"starts_with(lower(text_uppercase) "test")"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            ("is_contained(int @array)", vec![Ok(true), Ok(true)]),
+            ("exists(map) && !exists(missing)", vec![Ok(true), Ok(true)]),
+            ("any(array, compare(\"\" > 2))", vec![Ok(false), Ok(true)]),
+            (
+                "compare(map.first == 1)",
+                vec![
+                    Err(FilsonError::ExtractionError),
+                    Err(FilsonError::ExtractionError),
+                ],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn native_paths_in_json() {
+        use filson::{get_filter, Appliable};
+        use serde_json::json;
+
+        let data = json!({
+            "a": {"b": [{"weird key": 1, "c.d": 2}, {"weird key": 3}], "true": 4, "x/y": 5},
+            "items": [{"price": 150, "tags": ["sale"]}, {"price": 20, "tags": []}],
+        });
+        let conditions = [
+            (r#"compare(a.b[0]."weird key" == 1)"#, Ok(true)),
+            (r#"compare(a.b[0]["c.d"] == 2)"#, Ok(true)),
+            (r#"compare(a.b[1]."weird key" == @a.b[0]."c.d")"#, Ok(false)),
+            (r#"compare(a.b[*]."weird key" == 3)"#, Ok(true)),
+            (r#"compare(a.b.*."weird key" > 3)"#, Ok(false)),
+            (r#"compare(a["b"][1]["weird key"] == 3)"#, Ok(true)),
+            // keys are never escaped, unlike in json pointers
+            (r#"compare(a.true == 4) && compare(a."x/y" == 5)"#, Ok(true)),
+            (r#"compare(a.b[*]."weird key" == @"/a/x~1y")"#, Ok(false)),
+            ("any(items, compare(price > 100))", Ok(true)),
+            ("all(items, compare(price > 100))", Ok(false)),
+            ("count(items, compare(len(tags) > 0)) == 1", Ok(true)),
+            (
+                "compare(items[2].price == 1)",
+                Err(FilsonError::ExtractionError),
+            ),
+            (
+                "compare(items.price == 1)",
+                Err(FilsonError::ExtractionError),
+            ),
+            (
+                "compare(items[*].price == @items[*].price)",
+                Err(FilsonError::MultipleValuesError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_binary_conditions {
+    use filson::{get_filter, Appliable, DataNode, Extractable, FilsonError, FilsonResult};
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn construct_binary_from_triplet(triplet: (&str, &str, &str)) -> String {
+        format!("{}({}, {})", triplet.0, triplet.1, triplet.2)
+    }
+
+    #[test]
+    fn and_legal_rhs_lhs() {
+        common::run_triplet_test(
+            (
+                "and",
+                r#"is_superset("array" [2])"#,
+                r#"compare("int" == 1)"#,
+            ),
+            vec![Ok(true), Ok(false)],
+            construct_binary_from_triplet,
+        )
+    }
+
+    #[test]
+    fn or_legal_rhs_lhs() {
+        common::run_triplet_test(
+            (
+                "or",
+                r#"is_superset("array" [2])"#,
+                r#"compare("int" == 1)"#,
+            ),
+            vec![Ok(true), Ok(true)],
+            construct_binary_from_triplet,
+        )
+    }
+
+    #[test]
+    fn xor_legal_rhs_lhs() {
+        common::run_triplet_test(
+            (
+                "xor",
+                r#"is_superset("array" [2])"#,
+                r#"compare("int" == 1)"#,
+            ),
+            vec![Ok(false), Ok(true)],
+            construct_binary_from_triplet,
+        )
+    }
+
+    #[test]
+    fn binary_illegal_rhs() {
+        // shouldn't be able to parse
+        // parser should not allow for incorrect types
+        let triplets = [
+            ("and", "1", r#"compare("int" == 1)"#),
+            ("or", "1", r#"compare("int" == 1)"#),
+            ("xor", "1", r#"compare("int" == 1)"#),
+            ("and", r#"compare("int" == 1)"#, "1"),
+            ("or", r#"compare("int" == 1)"#, "1"),
+            ("xor", r#"compare("int" == 1)"#, "1"),
+        ];
+        for triplet in triplets {
+            assert!(get_filter(&construct_binary_from_triplet(triplet)).is_err());
+        }
+    }
+
+    #[test]
+    fn variadic_legal_operands() {
+        let conditions = [
+            (
+                r#"and(exists("int"), compare("int" == 1), is_superset("array" [2]))"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"or(compare("int" == 3), compare("text" == "karl"), compare("int" == 1))"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"xor(compare("int" == 1), compare("boolean" == true), compare("text" == "karl"))"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"one_of(compare("int" == 1), compare("int" == 2), compare("int" == 3))"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"one_of(exists("int"), exists("float"), exists("fake"))"#,
+                vec![Ok(false), Ok(false)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn variadic_short_circuit() {
+        common::run_singlet_test(
+            r#"or(compare("int" == 2), compare("int" == 1), compare("fake" == 1))"#,
+            vec![Ok(true), Ok(true)],
+        );
+        common::run_singlet_test(
+            r#"and(exists("int"), compare("int" == 2), compare("fake" == 1))"#,
+            vec![Ok(false), Err(FilsonError::ExtractionError)],
+        );
+        // xor can stop once the second true operand is found
+        common::run_singlet_test(
+            r#"xor(exists("int"), exists("float"), compare("fake" == 1))"#,
+            vec![Ok(false), Ok(false)],
+        );
+    }
+
+    #[test]
+    fn and_or_short_circuit() {
+        // right side fails to extract, but is never evaluated when left side decides the result
+        common::run_singlet_test(
+            r#"or(compare("int" == 1), compare("fake" > 1))"#,
+            vec![Ok(true), Err(FilsonError::ExtractionError)],
+        );
+        common::run_singlet_test(
+            r#"and(compare("int" == 2), compare("fake" > 1))"#,
+            vec![Ok(false), Err(FilsonError::ExtractionError)],
+        );
+        common::run_singlet_test(
+            r#"or(!exists("fake"), compare("fake" > 1))"#,
+            vec![Ok(true), Ok(true)],
+        );
+    }
+
+    #[test]
+    fn xor_does_not_short_circuit() {
+        common::run_singlet_test(
+            r#"xor(compare("int" == 1), compare("fake" > 1))"#,
+            vec![
+                Err(FilsonError::ExtractionError),
+                Err(FilsonError::ExtractionError),
+            ],
+        );
+    }
+
+    #[test]
+    fn short_circuit_skips_extraction() {
+        struct Counting(Cell<usize>);
+
+        impl Extractable for Counting {
+            fn extract(&self, _path: &str) -> FilsonResult<DataNode<'_>> {
+                self.0.set(self.0.get() + 1);
+                Ok(DataNode::I64(1))
+            }
+        }
+
+        let data = Counting(Cell::new(0));
+        let flt = get_filter(r#"or(compare("a" == 1), compare("b" == 1))"#).unwrap();
+        assert_eq!(flt.apply(&data), Ok(true));
+        assert_eq!(data.0.get(), 1);
+
+        let data = Counting(Cell::new(0));
+        let flt = get_filter(r#"and(compare("a" == 2), compare("b" == 1))"#).unwrap();
+        assert_eq!(flt.apply(&data), Ok(false));
+        assert_eq!(data.0.get(), 1);
+    }
+
+    #[test]
+    fn nesting_legal_rhs_lhs() {
+        common::run_triplet_test(
+            (
+                "xor",
+                r#"or(is_superset("array" [3]), compare("int" == 1))"#,
+                r#"and(compare("int" == 2), is_subset("text" "karlissimo"))"#,
+            ),
+            vec![Ok(true), Ok(false)],
+            construct_binary_from_triplet,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_negation {
+    use super::*;
+    use filson::get_filter;
+
+    #[test]
+    fn negates_valid_thing() {
+        let conditions = [
+            r#"!compare("int" == 1)"#,
+            r#"!intersects("text" "text")"#,
+            r#"!is_contained("int" [1, 3])"#,
+            r#"!is_subset("array" [1, 2, 5])"#,
+            r#"!is_superset("array" [1])"#,
+            r#"!and(is_contained("int" [1, 2, 3]), compare("int" == 1))"#,
+            r#"!or(compare("float" == 1.0), compare("int" == 1))"#,
+            r#"!xor(is_contained("int" [1, 3]), is_contained("int" [3, 5]))"#,
+        ];
+        for cond in conditions {
+            common::run_singlet_test(cond, vec![Ok(false), Ok(true)]);
+        }
+        common::run_singlet_test(r#"!exists("int")"#, vec![Ok(false), Ok(false)]);
+        common::run_singlet_test(r#"!exists("fake")"#, vec![Ok(true), Ok(true)]);
+    }
+
+    #[test]
+    fn negates_infix_thing() {
+        common::run_singlet_test(
+            r#"!(compare("int" == 1) || compare("text" == "test text"))"#,
+            vec![Ok(false), Ok(true)],
+        );
+        common::run_singlet_test(r#"!!exists("int")"#, vec![Ok(true), Ok(true)]);
+    }
+
+    #[test]
+    fn negates_invalid_thing() {
+        // shouldn't be able to parse
+        // parser should not allow for incorrect types
+        assert!(get_filter("!1").is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_owned_filter {
+    use super::*;
+    use filson::{Appliable, Filter};
+    use std::{sync::Arc, thread};
+
+    fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+
+    #[test]
+    fn is_shareable() {
+        assert_shareable::<Filter>();
+    }
+
+    #[test]
+    fn outlives_input() {
+        let cond = String::from(r#"and(compare("text" == "karl"), is_contained("int" [2]))"#);
+        let flt = Filter::new(&cond).unwrap();
+        drop(cond);
+        let test_data = setup::get_test_data();
+        let actual = test_data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>();
+        assert_eq!(actual, vec![Ok(false), Ok(true)]);
+        assert_eq!(
+            flt.source(),
+            r#"and(compare("text" == "karl"), is_contained("int" [2]))"#
+        );
+    }
+
+    #[test]
+    fn clones_are_applied_across_threads() {
+        let flt: Filter = r#"compare("int" == 1)"#.parse().unwrap();
+        let shared = Arc::new(flt.clone());
+        let handles = (0..4)
+            .map(|_| {
+                let flt = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let test_data = setup::get_test_data();
+                    test_data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), vec![Ok(true), Ok(false)]);
+        }
+        let test_data = setup::get_test_data();
+        assert_eq!(flt.apply(&test_data[0]), Ok(true));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(Filter::new("!1").is_err());
+        assert!("compare(1 == 1)".parse::<Filter>().is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_data_node {
+    use chrono::{DateTime, Utc};
+    use filson::DataNode;
+    use rust_decimal::Decimal;
+    use std::{
+        borrow::Cow,
+        collections::{BTreeSet, HashSet},
+        time::SystemTime,
+    };
+
+    #[test]
+    fn borrowed_and_owned_strings_are_equal() {
+        let owned = String::from("karl");
+        assert_eq!(DataNode::from("karl"), DataNode::from(owned.clone()));
+        assert_eq!(DataNode::from(Cow::Borrowed("karl")), DataNode::from(owned));
+    }
+
+    #[test]
+    fn datetimes_are_equal_by_instant() {
+        let utc = DateTime::parse_from_rfc3339("1970-01-01T00:00:00Z").unwrap();
+        let shifted = DateTime::parse_from_rfc3339("1970-01-01T02:00:00+02:00").unwrap();
+        assert_eq!(DataNode::from(utc), DataNode::from(shifted));
+        assert_eq!(
+            DataNode::from(utc.with_timezone(&Utc)),
+            DataNode::from(shifted)
+        );
+        assert_eq!(
+            DataNode::from(SystemTime::UNIX_EPOCH),
+            DataNode::from(shifted)
+        );
+    }
+
+    #[test]
+    fn integers_are_equal_by_value() {
+        assert_eq!(DataNode::from(1_i64), DataNode::from(1_u64));
+        assert_eq!(DataNode::from(1_u8), DataNode::from(1_i128));
+        assert_eq!(DataNode::from(u64::MAX), DataNode::from(u64::MAX as u128));
+        assert_ne!(DataNode::from(-1_i64), DataNode::from(u128::MAX));
+        assert_ne!(DataNode::from(1_i64), DataNode::from(1.0));
+
+        let set = BTreeSet::from([DataNode::from(1_i64), DataNode::from(1_u64)]);
+        assert_eq!(set.len(), 1);
+        let hashed = HashSet::from([DataNode::from(2_i128), DataNode::from(2_u32)]);
+        assert_eq!(hashed.len(), 1);
+    }
+
+    #[test]
+    fn integers_are_ordered_across_widths() {
+        let mut nodes = vec![
+            DataNode::from(u128::MAX),
+            DataNode::from(u64::MAX),
+            DataNode::from(i128::MIN),
+            DataNode::from(-1_i64),
+            DataNode::from(i64::MAX),
+        ];
+        nodes.sort();
+        assert_eq!(
+            nodes,
+            vec![
+                DataNode::I128(i128::MIN),
+                DataNode::I64(-1),
+                DataNode::I64(i64::MAX),
+                DataNode::U64(u64::MAX),
+                DataNode::U128(u128::MAX),
+            ]
+        );
+        // integers keep their place between the other types
+        assert!(DataNode::from(u128::MAX) < DataNode::from(f64::MIN));
+        assert!(DataNode::from(i128::MIN) > DataNode::from(Vec::new()));
+    }
+
+    #[test]
+    fn decimals_are_exact() {
+        let tenth = Decimal::new(1, 1);
+        let fifth = Decimal::new(2, 1);
+        assert_eq!(
+            DataNode::from(tenth + fifth),
+            DataNode::from(Decimal::new(3, 1))
+        );
+        assert_eq!(
+            DataNode::from(Decimal::new(150, 2)),
+            DataNode::from(Decimal::new(15, 1))
+        );
+        assert_ne!(DataNode::from(Decimal::ONE), DataNode::from(1));
+        assert_ne!(DataNode::from(Decimal::ONE), DataNode::from(1.0));
+    }
+
+    #[test]
+    fn extracts_by_json_pointer() {
+        use filson::{Extractable, FilsonError};
+        use std::collections::BTreeMap;
+
+        let node = DataNode::from(BTreeMap::from([
+            (
+                DataNode::from("items"),
+                DataNode::from(vec![DataNode::from(1), DataNode::from(2)]),
+            ),
+            (DataNode::from("a/b~c"), DataNode::from(true)),
+            (DataNode::from(1), DataNode::from(false)),
+        ]));
+        assert_eq!(node.extract(""), Ok(node.clone()));
+        assert_eq!(node.extract("/items/1"), Ok(DataNode::from(2)));
+        assert_eq!(node.extract("/a~1b~0c"), Ok(DataNode::from(true)));
+        for path in [
+            "items",
+            "/items/2",
+            "/items/01",
+            "/items/+1",
+            "/items/0/a",
+            "/1",
+        ] {
+            assert_eq!(
+                node.extract(path),
+                Err(FilsonError::ExtractionError),
+                "{path}"
+            );
+        }
+
+        assert_eq!(
+            node.extract_all("/items/*"),
+            Ok(vec![DataNode::from(1), DataNode::from(2)])
+        );
+        assert_eq!(node.extract_all("/items/0"), Ok(vec![DataNode::from(1)]));
+        // values without the rest of the path are skipped
+        assert_eq!(node.extract_all("/*/1"), Ok(vec![DataNode::from(2)]));
+        assert_eq!(node.extract_all("/*/2"), Err(FilsonError::ExtractionError));
+        assert_eq!(node.extract_all("$..[1]"), Ok(vec![DataNode::from(2)]));
+        assert_eq!(
+            node.extract_all("$['a/b~c', 'items']"),
+            Ok(vec![
+                DataNode::from(true),
+                DataNode::from(vec![DataNode::from(1), DataNode::from(2)])
+            ])
+        );
+    }
+
+    #[test]
+    fn into_owned_detaches_from_input() {
+        let detached: DataNode<'static> = {
+            let text = String::from("karl");
+            DataNode::from(vec![DataNode::from(text.as_str()), DataNode::from(1)]).into_owned()
+        };
+        assert_eq!(
+            detached,
+            DataNode::from(vec![DataNode::from("karl"), DataNode::from(1)])
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test_escapes {
+    use filson::{get_filter, Appliable, FilsonError};
+    use pest::error::InputLocation;
+    use serde_json::json;
+
+    #[test]
+    fn escaped_literals_match_decoded_data() {
+        let data = json!({"caf\u{e9}": "line\nbreak", "emoji": "\u{1F980}", "a/b": true});
+        let conditions = [
+            r#"compare("/café" == "line\nbreak")"#,
+            r#"compare("/emoji" == "\uD83E\uDD80")"#,
+            r#"compare("/emoji" == "🦀")"#,
+            r#"compare("/a~1b" == true)"#,
+        ];
+        for cond in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), Ok(true), "{cond}");
+        }
+    }
+
+    #[test]
+    fn invalid_escapes_are_rejected() {
+        let conditions = [
+            // lone low surrogate
+            (r#"compare("/id" == "\uDD80")"#, (18, 24)),
+            // lone high surrogate
+            (r#"exists("\uD83E")"#, (8, 14)),
+            // high surrogate followed by something else than a low surrogate
+            (r#"compare("/id" == "a\uD83E\u0041")"#, (19, 31)),
+            (r#"compare("/id" == "\uD83Eb")"#, (18, 24)),
+        ];
+        for (cond, span) in conditions {
+            let Err(FilsonError::ParseError(err)) = get_filter(cond) else {
+                panic!("{cond} should fail to parse");
+            };
+            assert_eq!(err.location, InputLocation::Span(span), "{cond}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_infix_conditions {
+    use super::*;
+    use filson::FilsonError;
+
+    #[test]
+    fn infix_legal_operands() {
+        let conditions = [
+            (
+                r#"compare("int" == 1) && is_superset("array" [2])"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare("int" == 1) || compare("text" == "karl")"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"is_superset("array" [2]) ^^ compare("int" == 1)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"exists("int") ^^ exists("text") ^^ exists("array")"#,
+                vec![Ok(false), Ok(false)],
+            ),
+            (
+                r#"(exists("int") ^^ exists("text")) ^^ exists("array")"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"exists("int") ^^ (exists("text") ^^ exists("array"))"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"compare("int" == 1) ^^ compare("int" == 2) ^^ exists("fake")"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"exists("int") && compare("int" == 2) || !exists("fake")"#,
+                vec![Ok(true), Ok(true)],
+            ),
+            (
+                r#"exists("int") && (compare("int" == 2) || exists("fake"))"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"and(compare("int" == 2) || compare("int" == 1), !exists("fake"))"#,
+                vec![Ok(true), Ok(true)],
+            ),
+        ];
+        for (cond, expected) in conditions {
+            common::run_singlet_test(cond, expected);
+        }
+    }
+
+    #[test]
+    fn infix_short_circuit() {
+        common::run_singlet_test(
+            r#"!exists("fake") || compare("fake" > 1)"#,
+            vec![Ok(true), Ok(true)],
+        );
+        common::run_singlet_test(
+            r#"compare("int" == 2) && compare("fake" > 1)"#,
+            vec![Ok(false), Err(FilsonError::ExtractionError)],
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test_datetime_strings {
+    use filson::{get_filter, Appliable, FilsonError};
+    use serde_json::json;
+
+    #[test]
+    fn rfc3339_strings_compare_as_datetimes() {
+        let data = json!({"created_at": "2024-01-01T12:00:00+01:00", "name": "karl"});
+        let conditions = [
+            (
+                r#"compare("/created_at" == t"2024-01-01T11:00:00Z")"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("/created_at" > t"2024-01-01T11:30:00Z")"#,
+                Ok(false),
+            ),
+            (
+                r#"compare("/created_at" == "2024-01-01T11:00:00Z")"#,
+                Ok(false),
+            ),
+            (
+                r#"compare("/name" == t"2024-01-01T11:00:00Z")"#,
+                Err(FilsonError::TypeError),
+            ),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_relative_time {
+    use std::cell::Cell;
+
+    use chrono::{DateTime, Utc};
+    use filson::{get_filter, Appliable, Clock, FilsonError, Filter};
+
+    use super::*;
+
+    fn clock() -> DateTime<Utc> {
+        "2024-06-08T10:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn now_is_read_from_the_clock() {
+        let conditions = [
+            (
+                r#"compare("datetime" >= now() - 7d)"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            (
+                r#"compare("datetime" > now() - 7d)"#,
+                vec![Ok(false), Ok(false)],
+            ),
+            (r#"compare("datetime" < now())"#, vec![Ok(true), Ok(true)]),
+            (
+                r#"compare("datetime" < now() + -P1W)"#,
+                vec![Ok(true), Ok(false)],
+            ),
+            (
+                r#"compare("text" == now())"#,
+                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
+            ),
+            (
+                r#"compare("datetime" < now() + 100000000d)"#,
+                vec![
+                    Err(FilsonError::OverflowError),
+                    Err(FilsonError::OverflowError),
+                ],
+            ),
+        ];
+        let test_data = setup::get_test_data();
+        for (cond, expected) in conditions {
+            let flt = get_filter(cond).unwrap();
+            let actual = test_data
+                .iter()
+                .map(|d| flt.apply_with_clock(d, &clock()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{cond}");
+
+            let flt = Filter::new(cond).unwrap();
+            let actual = test_data
+                .iter()
+                .map(|d| flt.apply_with_clock(d, &clock()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{cond}");
         }
     }
 
     #[test]
-    fn doesnt_exist() {
-        let fake_path = "fake";
-        let test_data = setup::get_test_data();
-        let cond = construct_exists(fake_path);
-        let flt = get_filter(&cond).unwrap();
-        let actual = test_data.iter().map(|d| flt.apply(d)).collect::<Vec<_>>();
-        assert_eq!(actual, vec![Ok(false), Ok(false)]);
+    fn system_clock_is_used_by_default() {
+        let flt = get_filter(r#"compare("datetime" < now())"#).unwrap();
+        for d in setup::get_test_data() {
+            assert_eq!(flt.apply(&d), Ok(true));
+        }
     }
-}
 
-#[cfg(test)]
-mod test_is_subset {
-    use super::*;
-    use filson::{get_filter, FilsonError};
+    struct CountingClock(Cell<usize>);
 
-    fn construct_is_subset_from_doublet(doublet: (&str, &str)) -> String {
-        format!(r#"is_subset("{}" {})"#, doublet.0, doublet.1)
+    impl Clock for CountingClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0.set(self.0.get() + 1);
+            clock()
+        }
     }
 
     #[test]
-    fn is_subset_legal_lhs_rhs() {
-        let doublets = [
-            ("array", "[2, 3, 5]"),
-            ("set", "{2, 3, 5}"),
-            ("map", r#"<"second": 2, "third": 3, "fifth": 5>"#),
-            ("text", r#""karlissimo""#),
-        ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![Ok(false), Ok(true)],
-                construct_is_subset_from_doublet,
-            );
+    fn clock_is_read_once_per_evaluation() {
+        let flt =
+            get_filter(r#"compare("datetime" < now()) && compare("datetime" > now() - 1000d)"#)
+                .unwrap();
+        let counting_clock = CountingClock(Cell::new(0));
+        for d in setup::get_test_data() {
+            assert_eq!(flt.apply_with_clock(&d, &counting_clock), Ok(true));
         }
+        assert_eq!(counting_clock.0.get(), 2);
+
+        let flt = get_filter(r#"compare("int" == 1)"#).unwrap();
+        let counting_clock = CountingClock(Cell::new(0));
+        flt.apply_with_clock(&setup::get_test_data()[0], &counting_clock)
+            .unwrap();
+        assert_eq!(counting_clock.0.get(), 0);
     }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test_duration_strings {
+    use filson::{get_filter, Appliable, FilsonError};
+    use serde_json::json;
 
     #[test]
-    fn is_subset_illegal_lhs() {
-        let doublets = [
-            ("int", "[1, 2, 5]"),
-            ("float", "{1, 2, 5}"),
-            ("boolean", r#"<"first": 1, "second": 2, "fifth": 5>"#),
+    fn duration_strings_compare_as_durations() {
+        let data =
+            json!({"ttl": "PT1H", "timeout": "90m", "name": "karl", "empty": "", "sign": "-"});
+        let conditions = [
+            (r#"compare("/ttl" == 60m)"#, Ok(true)),
+            (r#"compare("/timeout" > PT1H)"#, Ok(true)),
+            (r#"compare("/ttl" == "60m")"#, Ok(false)),
+            (r#"compare("/name" == 1h)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/empty" < 1h)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/sign" == 0s)"#, Err(FilsonError::TypeError)),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![
-                    Err(FilsonError::IsSubsetError),
-                    Err(FilsonError::IsSubsetError),
-                ],
-                construct_is_subset_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
     }
+}
 
-    #[test]
-    fn is_subset_illegal_rhs() {
-        // shouldn't be able to parse
-        // parser should not allow for incorrect types
-        let rhsses = ["1", "1.0", "true", "null"];
-        for rhs in rhsses {
-            assert!(get_filter(&construct_is_subset_from_doublet(("whatever", rhs))).is_err());
-        }
-    }
+#[cfg(all(test, feature = "serde_json"))]
+mod test_wide_integers {
+    use filson::{get_filter, Appliable};
+    use serde_json::json;
 
     #[test]
-    fn is_subset_unmatching_lhs_rhs() {
-        let doublets = [
-            ("set", "[2, 3, 5]"),
-            ("array", "{2, 3, 5}"),
-            ("text", r#"<"second": 2, "third": 3, "fifth": 5>"#),
-            ("map", r#""karlissimo""#),
+    fn u64_above_i64_max_keeps_precision() {
+        let data = json!({"id": u64::MAX - 1, "counter": 1_u64});
+        let conditions = [
+            (r#"compare("/id" == 18446744073709551614)"#, Ok(true)),
+            (r#"compare("/id" == 18446744073709551615)"#, Ok(false)),
+            (r#"compare("/id" < 0xFFFF_FFFF_FFFF_FFFF)"#, Ok(true)),
+            (r#"compare("/id" > 9223372036854775807)"#, Ok(true)),
+            (
+                r#"compare("/id" < 340282366920938463463374607431768211455)"#,
+                Ok(true),
+            ),
+            (r#"compare("/counter" == 1)"#, Ok(true)),
+            (
+                r#"compare("/counter" > -170141183460469231731687303715884105728)"#,
+                Ok(true),
+            ),
+            (r#"is_contained("/id" [1, 18446744073709551614])"#, Ok(true)),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
-                construct_is_subset_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
     }
 }
 
-#[cfg(test)]
-mod test_is_superset {
-    use super::*;
-    use filson::{get_filter, FilsonError};
-
-    fn construct_is_superset_from_doublet(doublet: (&str, &str)) -> String {
-        format!(r#"is_superset("{}" {})"#, doublet.0, doublet.1)
-    }
+#[cfg(all(test, feature = "serde_json"))]
+mod test_decimals {
+    use filson::{get_filter, Appliable, FilsonError};
+    use serde_json::json;
 
     #[test]
-    fn is_superset_legal_lhs_rhs() {
-        let doublets = [
-            ("array", "[3]"),
-            ("set", "{3}"),
-            ("map", r#"<"third": 3>"#),
-            ("text", r#""ka""#),
+    fn decimal_strings_compare_as_decimals() {
+        let data = json!({"price": "0.30", "total": "1000000000000000000.01", "name": "karl"});
+        let conditions = [
+            (r#"compare("/price" == 0.3d)"#, Ok(true)),
+            (r#"compare("/price" < 0.30000000000000001d)"#, Ok(true)),
+            (
+                r#"compare("/total" > 1_000_000_000_000_000_000.0d)"#,
+                Ok(true),
+            ),
+            (r#"compare("/price" == 0.3)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/name" == 0.3d)"#, Err(FilsonError::TypeError)),
+            (r#"is_contained("/price" [0.1d, 0.30d])"#, Ok(false)),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![Ok(false), Ok(true)],
-                construct_is_superset_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
     }
 
     #[test]
-    fn is_superset_illegal_lhs() {
-        let doublets = [
-            ("int", "[1, 2, 5]"),
-            ("float", "{1, 2, 5}"),
-            ("boolean", r#"<"first": 1, "second": 2, "fifth": 5>"#),
+    fn integer_with_d_suffix_is_a_duration() {
+        let data = json!({"amount": 100, "price": "100"});
+        let conditions = [
+            (r#"compare("/amount" == 100d)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/price" == 100d)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/price" == 100.0d)"#, Ok(true)),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![
-                    Err(FilsonError::IsSupersetError),
-                    Err(FilsonError::IsSupersetError),
-                ],
-                construct_is_superset_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
+        // the type is known before the filter is applied
+        assert!(get_filter(r#"compare(abs("/amount") == 100d)"#).is_err());
     }
+}
 
-    #[test]
-    fn is_superset_illegal_rhs() {
-        // shouldn't be able to parse
-        // parser should not allow for incorrect types
-        let rhsses = ["1", "1.0", "true", "null"];
-        for rhs in rhsses {
-            assert!(get_filter(&construct_is_superset_from_doublet(("whatever", rhs))).is_err());
-        }
-    }
+#[cfg(all(test, feature = "arbitrary_precision"))]
+mod test_arbitrary_precision {
+    use filson::{get_filter, Appliable, FilsonError};
+    use serde_json::Value;
 
     #[test]
-    fn is_superset_unmatching_lhs_rhs() {
-        let doublets = [
-            ("set", "[2, 3, 5]"),
-            ("array", "{2, 3, 5}"),
-            ("text", r#"<"second": 2, "third": 3, "fifth": 5>"#),
-            ("map", r#""karlissimo""#),
+    fn numbers_are_kept_exact() {
+        let data: Value = serde_json::from_str(
+            r#"{"amount": 0.1, "big": 123456789012345678901234567890, "tiny": 1e-3}"#,
+        )
+        .unwrap();
+        let conditions = [
+            (r#"compare("/amount" == 0.1d)"#, Ok(true)),
+            (r#"compare("/amount" == 0.1)"#, Err(FilsonError::TypeError)),
+            (
+                r#"compare("/big" == 123456789012345678901234567890)"#,
+                Ok(true),
+            ),
+            (
+                r#"compare("/big" > 123456789012345678901234567889)"#,
+                Ok(true),
+            ),
+            (r#"compare("/tiny" == 0.001d)"#, Ok(true)),
         ];
-        for doublet in doublets {
-            common::run_doublet_test(
-                doublet,
-                vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)],
-                construct_is_superset_from_doublet,
-            );
+        for (cond, expected) in conditions {
+            assert_eq!(get_filter(cond).unwrap().apply(&data), expected, "{cond}");
         }
     }
 }
 
-#[cfg(test)]
-mod test_binary_conditions {
-    use filson::get_filter;
-
-    use super::*;
+#[cfg(all(test, feature = "serde_json"))]
+mod test_numeric_coercion {
+    use filson::{Appliable, FilsonError, Filter, FilterOptions};
+    use serde_json::json;
 
-    fn construct_binary_from_triplet(triplet: (&str, &str, &str)) -> String {
-        format!("{}({}, {})", triplet.0, triplet.1, triplet.2)
+    fn coercing_filter(cond: &str) -> Filter {
+        Filter::with_options(cond, FilterOptions::default().numeric_coercion(true)).unwrap()
     }
 
     #[test]
-    fn and_legal_rhs_lhs() {
-        common::run_triplet_test(
+    fn integers_and_floats_compare_by_value() {
+        let data = json!({
+            "price": 10.5,
+            "n": 1,
+            "whole": 2.0,
+            "big": 9007199254740992.0,
+            "max": u64::MAX,
+            "name": "karl",
+            "obj": {"a": 1, "b": [1, 2.5]},
+        });
+        let conditions = [
+            (r#"compare("/price" > 10)"#, Ok(true)),
+            (r#"compare("/price" <= 10)"#, Ok(false)),
+            (r#"compare("/n" == 1.0)"#, Ok(true)),
+            (r#"compare("/n" != 1.5)"#, Ok(true)),
+            (r#"compare("/n" < 1.5)"#, Ok(true)),
+            (r#"compare("/whole" == 2)"#, Ok(true)),
+            (r#"compare("/big" == 9007199254740992)"#, Ok(true)),
+            (r#"compare("/big" < 9007199254740993)"#, Ok(true)),
+            (r#"compare("/max" < 18446744073709551615.0)"#, Ok(true)),
             (
-                "and",
-                r#"is_superset("array" [2])"#,
-                r#"compare("int" == 1)"#,
+                r#"compare("/obj" == <"a": 1.0, "b": [1.0, 2.5]>)"#,
+                Ok(true),
             ),
-            vec![Ok(true), Ok(false)],
-            construct_binary_from_triplet,
-        )
+            (r#"compare("/name" == 1)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/n" == 1.0d)"#, Ok(true)),
+            (r#"compare("/price" == 10.50d)"#, Ok(true)),
+            (r#"compare("/price" < 10.6d)"#, Ok(true)),
+            (r#"is_contained("/price" [10.5d])"#, Ok(true)),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(coercing_filter(cond).apply(&data), expected, "{cond}");
+        }
     }
 
     #[test]
-    fn or_legal_rhs_lhs() {
-        common::run_triplet_test(
+    fn coercion_applies_to_collections() {
+        let data = json!({"n": 2, "f": 2.0, "arr": [1, 2], "floats": [1.0, 2.5]});
+        let conditions = [
+            (r#"is_contained("/n" [1.0, 2.0])"#, Ok(true)),
+            (r#"is_contained("/f" [1, 2])"#, Ok(true)),
+            (r#"is_contained("/n" [2.5])"#, Ok(false)),
+            (r#"intersects("/arr" [2.0, 3.0])"#, Ok(true)),
             (
-                "or",
-                r#"is_superset("array" [2])"#,
-                r#"compare("int" == 1)"#,
+                r#"intersects("/floats" {1, 3})"#,
+                Err(FilsonError::TypeError),
             ),
-            vec![Ok(true), Ok(true)],
-            construct_binary_from_triplet,
-        )
+            (r#"intersects("/floats" [1, 3])"#, Ok(true)),
+            (r#"is_subset("/arr" [1.0, 2.0, 3.0])"#, Ok(true)),
+            (r#"is_superset("/floats" [1])"#, Ok(true)),
+            (r#"is_superset("/floats" [2])"#, Ok(false)),
+        ];
+        for (cond, expected) in conditions {
+            assert_eq!(coercing_filter(cond).apply(&data), expected, "{cond}");
+        }
     }
 
     #[test]
-    fn xor_legal_rhs_lhs() {
-        common::run_triplet_test(
+    fn decimals_compare_exactly_with_integers() {
+        let data = json!({"n": i64::MAX, "whole": "3.00", "fraction": "0.25"});
+        let conditions = [
+            (r#"compare("/n" == 9223372036854775807.0d)"#, Ok(true)),
+            (r#"compare("/n" < 9223372036854775807.1d)"#, Ok(true)),
+            (r#"compare("/n" > 0.5d)"#, Ok(true)),
             (
-                "xor",
-                r#"is_superset("array" [2])"#,
-                r#"compare("int" == 1)"#,
+                r#"compare("/n" < 340282366920938463463374607431768211455)"#,
+                Ok(true),
             ),
-            vec![Ok(false), Ok(true)],
-            construct_binary_from_triplet,
-        )
-    }
-
-    #[test]
-    fn binary_illegal_rhs() {
-        // shouldn't be able to parse
-        // parser should not allow for incorrect types
-        let triplets = [
-            ("and", "1", r#"compare("int" == 1)"#),
-            ("or", "1", r#"compare("int" == 1)"#),
-            ("xor", "1", r#"compare("int" == 1)"#),
-            ("and", r#"compare("int" == 1)"#, "1"),
-            ("or", r#"compare("int" == 1)"#, "1"),
-            ("xor", r#"compare("int" == 1)"#, "1"),
+            (r#"compare("/whole" == 3.0d)"#, Ok(true)),
+            (r#"compare("/fraction" == 0.25d)"#, Ok(true)),
         ];
-        for triplet in triplets {
-            assert!(get_filter(&construct_binary_from_triplet(triplet)).is_err());
+        for (cond, expected) in conditions {
+            assert_eq!(coercing_filter(cond).apply(&data), expected, "{cond}");
         }
     }
 
     #[test]
-    fn nesting_legal_rhs_lhs() {
-        common::run_triplet_test(
-            (
-                "xor",
-                r#"or(is_superset("array" [3]), compare("int" == 1))"#,
-                r#"and(compare("int" == 2), is_subset("text" "karlissimo"))"#,
-            ),
-            vec![Ok(true), Ok(false)],
-            construct_binary_from_triplet,
-        )
+    fn coercion_is_disabled_by_default() {
+        let data = json!({"price": 10.5, "n": 1});
+        let conditions = [
+            (r#"compare("/price" > 10)"#, Err(FilsonError::TypeError)),
+            (r#"compare("/n" == 1.0)"#, Err(FilsonError::TypeError)),
+            (r#"is_contained("/n" [1.0])"#, Ok(false)),
+        ];
+        for (cond, expected) in conditions {
+            let flt = Filter::new(cond).unwrap();
+            assert_eq!(flt.options(), FilterOptions::default());
+            assert_eq!(flt.apply(&data), expected, "{cond}");
+        }
     }
 }
 
 #[cfg(test)]
-mod test_negation {
+mod test_params {
+    use chrono::{DateTime, Utc};
+    use filson::{get_filter, Appliable, DataNode, FilsonError, Filter, Params};
+
     use super::*;
-    use filson::get_filter;
 
     #[test]
-    fn negates_valid_thing() {
+    fn params_are_bound_on_apply() {
+        let params = Params::new()
+            .bind("one", 1)
+            .bind("name", "karl")
+            .bind("ints", vec![DataNode::from(2), DataNode::from(3)]);
+        let type_error = || vec![Err(FilsonError::TypeError), Err(FilsonError::TypeError)];
         let conditions = [
-            r#"!compare("int" == 1)"#,
-            r#"!intersects("text" "text")"#,
-            r#"!is_contained("int" [1, 3])"#,
-            r#"!is_subset("array" [1, 2, 5])"#,
-            r#"!is_superset("array" [1])"#,
-            r#"!and(is_contained("int" [1, 2, 3]), compare("int" == 1))"#,
-            r#"!or(compare("float" == 1.0), compare("int" == 1))"#,
-            r#"!xor(is_contained("int" [1, 3]), is_contained("int" [3, 5]))"#,
+            ("compare(int == $one)", vec![Ok(true), Ok(false)]),
+            ("compare(text != $name)", vec![Ok(true), Ok(false)]),
+            ("compare(int * 2 > $one)", vec![Ok(true), Ok(true)]),
+            ("between(int $one $one)", vec![Ok(true), Ok(false)]),
+            ("between(int ($one, 3])", vec![Ok(false), Ok(true)]),
+            ("is_contained(int $ints)", vec![Ok(false), Ok(true)]),
+            ("intersects(array $ints)", vec![Ok(true), Ok(true)]),
+            ("is_subset(array $ints)", vec![Ok(false), Ok(true)]),
+            ("is_superset(array $ints)", vec![Ok(false), Ok(true)]),
+            (
+                r#"all(array, compare("" > $one))"#,
+                vec![Ok(false), Ok(true)],
+            ),
+            ("compare(int == $name)", type_error()),
+            ("is_contained(int $one)", type_error()),
         ];
-        for cond in conditions {
-            common::run_singlet_test(cond, vec![Ok(false), Ok(true)]);
+        let test_data = setup::get_test_data();
+        for (cond, expected) in conditions {
+            let flt = get_filter(cond).unwrap();
+            let actual = test_data
+                .iter()
+                .map(|d| flt.apply_with(d, &params))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{cond}");
+
+            let flt = Filter::new(cond).unwrap();
+            let actual = test_data
+                .iter()
+                .map(|d| flt.apply_with(d, &params))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{cond}");
         }
-        common::run_singlet_test(r#"!exists("int")"#, vec![Ok(false), Ok(false)]);
-        common::run_singlet_test(r#"!exists("fake")"#, vec![Ok(true), Ok(true)]);
     }
 
     #[test]
-    fn negates_invalid_thing() {
-        // shouldn't be able to parse
-        // parser should not allow for incorrect types
-        assert!(get_filter("!1").is_err());
+    fn unbound_params_fail() {
+        let cond = "compare(int == 1) || compare(int == $b) || compare(int == $a)";
+        let test_data = setup::get_test_data();
+        let unbound = |name: &str| Err(FilsonError::UnboundParameterError(name.into()));
+
+        let flt = get_filter(cond).unwrap();
+        // all of the parameters are checked before the condition is evaluated
+        assert_eq!(flt.apply(&test_data[0]), unbound("a"));
+        assert_eq!(
+            flt.apply_with(&test_data[0], &Params::new().bind("a", 1)),
+            unbound("b")
+        );
+        assert_eq!(
+            flt.apply_with(&test_data[1], &Params::from_iter([("a", 3), ("b", 2)])),
+            Ok(true)
+        );
+
+        let flt = Filter::new(cond).unwrap();
+        assert_eq!(flt.params(), ["a", "b"]);
+        assert_eq!(flt.apply(&test_data[0]), unbound("a"));
+        assert_eq!(
+            flt.apply_with(&test_data[0], &Params::new().bind("a", 1)),
+            unbound("b")
+        );
+        assert_eq!(
+            flt.apply_with(&test_data[1], &Params::from_iter([("a", 3), ("b", 2)])),
+            Ok(true)
+        );
+        assert!(Filter::new("compare(int == 1)")
+            .unwrap()
+            .params()
+            .is_empty());
+    }
+
+    #[test]
+    fn params_are_values() {
+        let flt = Filter::new("compare(text == $text) && compare(datetime < now())").unwrap();
+        let clock: DateTime<Utc> = "2024-06-08T10:00:00Z".parse().unwrap();
+        let test_data = setup::get_test_data();
+        let params = Params::new().bind("text", r#"karl") || compare(int == 1"#);
+        assert_eq!(
+            flt.apply_with_params_and_clock(&test_data[0], &params, &clock),
+            Ok(false)
+        );
+        let params = params.bind("text", "test text");
+        assert_eq!(
+            flt.apply_with_params_and_clock(&test_data[0], &params, &clock),
+            Ok(true)
+        );
+        let clock: DateTime<Utc> = "2023-06-08T10:00:00Z".parse().unwrap();
+        assert_eq!(
+            flt.apply_with_params_and_clock(&test_data[0], &params, &clock),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn params_are_only_allowed_in_place_of_values() {
+        for cond in [
+            "compare($a == 1)",
+            "exists($a)",
+            "starts_with(text $a)",
+            "compare(int == [$a])",
+            "compare(int == $1)",
+            "compare(int == $ a)",
+        ] {
+            assert!(get_filter(cond).is_err(), "{cond}");
+        }
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use filson::{DataNode, Extractable, FilsonError, FilsonResult};
+use chrono::{DateTime, Duration, FixedOffset};
+use filson::{DataNode, Extractable, FilsonError, FilsonResult, Path, PathSegment};
 
 pub(crate) struct TestStruct {
     int: i64,
@@ -10,14 +11,17 @@ pub(crate) struct TestStruct {
     map: BTreeMap<&'static str, i64>,
     set: BTreeSet<i64>,
     array: Vec<i64>,
+    datetime: DateTime<FixedOffset>,
+    duration: Duration,
 }
 
 impl Extractable for TestStruct {
-    fn extract(&self, path: &str) -> FilsonResult<DataNode> {
+    fn extract(&self, path: &str) -> FilsonResult<DataNode<'_>> {
         match path {
             "int" => Ok(self.int.into()),
             "float" => Ok(self.float.into()),
             "text" => Ok(self.text.into()),
+            "text_uppercase" => Ok(self.text.to_uppercase().into()),
             "boolean" => Ok(self.boolean.into()),
             "null" => Ok(DataNode::Null),
             "map" => Ok(self
@@ -38,6 +42,15 @@ impl Extractable for TestStruct {
                 .map(|x| (*x).into())
                 .collect::<Vec<_>>()
                 .into()),
+            "datetime" => Ok(self.datetime.into()),
+            "duration" => Ok(self.duration.into()),
+            _ => Err(FilsonError::ExtractionError),
+        }
+    }
+
+    fn extract_path(&self, path: &Path) -> FilsonResult<Vec<DataNode<'_>>> {
+        match path.segments() {
+            [PathSegment::Key(key)] => self.extract(key).map(|node| vec![node]),
             _ => Err(FilsonError::ExtractionError),
         }
     }
@@ -52,6 +65,8 @@ pub(crate) fn get_test_data() -> Vec<TestStruct> {
         map: BTreeMap::from_iter(vec![("first", 1), ("second", 2)]),
         set: BTreeSet::from_iter(vec![1, 2]),
         array: vec![1, 2],
+        datetime: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap(),
+        duration: Duration::minutes(90),
     };
     let two = TestStruct {
         int: 2,
@@ -61,6 +76,8 @@ pub(crate) fn get_test_data() -> Vec<TestStruct> {
         map: BTreeMap::from_iter(vec![("second", 2), ("third", 3)]),
         set: BTreeSet::from_iter(vec![2, 3]),
         array: vec![2, 3],
+        datetime: DateTime::parse_from_rfc3339("2024-06-01T12:00:00+02:00").unwrap(),
+        duration: Duration::days(7),
     };
     vec![one, two]
 }